@@ -0,0 +1,16 @@
+//! Feeds arbitrary strings to the FEN parsers, run with `cargo +nightly fuzz run from_fen`
+
+#![no_main]
+
+use fritiofr_chess::{Board, Game};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|fen: &str| {
+    let _ = Board::from_fen(fen);
+    let _ = Game::from_fen_lenient(fen);
+
+    // Every game that parses has to write a FEN string that parses to the same game
+    if let Ok(game) = Game::from_fen(fen) {
+        assert_eq!(Game::from_fen(&game.fen()).unwrap(), game);
+    }
+});
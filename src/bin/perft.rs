@@ -0,0 +1,53 @@
+//! Counts the moves of a position like Stockfish's `go perft`
+//!
+//! Usage: `perft <fen> <depth>`
+//!
+//! The FEN can be given as one quoted argument or as separate words. Every legal move is printed
+//! in UCI notation with the number of leaf nodes below it, followed by the total, so the output
+//! can be diffed against Stockfish.
+
+use std::{env, process};
+
+use fritiofr_chess::Game;
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    let Some((depth, fen)) = args.split_last() else {
+        usage();
+    };
+    if fen.is_empty() {
+        usage();
+    }
+
+    let Ok(depth) = depth.parse::<u8>() else {
+        eprintln!("Invalid depth \"{}\"", depth);
+        process::exit(1);
+    };
+
+    let game = match Game::from_fen_lenient(&fen.join(" ")) {
+        Ok(game) => game,
+        Err(error) => {
+            eprintln!("Invalid FEN: {}", error);
+            process::exit(1);
+        }
+    };
+
+    let mut nodes = 0;
+    for (mv, count) in game.divide(depth) {
+        println!(
+            "{}: {}",
+            mv.uci().expect("Generated moves are on the board"),
+            count
+        );
+        nodes += count;
+    }
+
+    println!();
+    println!("Nodes searched: {}", nodes);
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: perft <fen> <depth>");
+    process::exit(1);
+}
@@ -0,0 +1,100 @@
+//! Runs a perft test suite in EPD format, like the classic `perftsuite.epd`
+//!
+//! Usage: `perft_suite <file.epd> [max depth] [reference engine]`
+//!
+//! Every position is checked up to the max depth, or every depth in the file if it's left out.
+//! Mismatches are printed with the divide of the position. If a reference engine that supports
+//! Stockfish's `go perft` is given, the moves where its divide differs are printed as well.
+
+use std::{
+    env, fs,
+    io::Write,
+    process::{self, Command, Stdio},
+};
+
+use fritiofr_chess::epd::{parse_divide, read_perft_suite, PerftMismatch};
+
+/// Runs `go perft` for the position of a mismatch in a reference engine and returns its divide
+fn reference_divide(engine: &str, mismatch: &PerftMismatch) -> std::io::Result<Vec<(String, u64)>> {
+    let mut child = Command::new(engine)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()?;
+
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    write!(
+        stdin,
+        "position fen {}\ngo perft {}\nquit\n",
+        mismatch.fen, mismatch.depth
+    )?;
+    drop(stdin);
+
+    let output = child.wait_with_output()?;
+    Ok(parse_divide(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    let Some(path) = args.first() else {
+        eprintln!("Usage: perft_suite <file.epd> [max depth] [reference engine]");
+        process::exit(1);
+    };
+
+    let max_depth = match args.get(1).map(|depth| depth.parse::<u8>()) {
+        None => u8::MAX,
+        Some(Ok(depth)) => depth,
+        Some(Err(_)) => {
+            eprintln!("Invalid max depth \"{}\"", args[1]);
+            process::exit(1);
+        }
+    };
+
+    let engine = args.get(2);
+
+    let epd = fs::read_to_string(path).unwrap_or_else(|error| {
+        eprintln!("Couldn't read {}: {}", path, error);
+        process::exit(1);
+    });
+
+    let suite = read_perft_suite(&epd).unwrap_or_else(|error| {
+        eprintln!("Couldn't parse {}: {}", path, error);
+        process::exit(1);
+    });
+
+    let mut failed = 0;
+    for (i, position) in suite.iter().enumerate() {
+        match position.check(max_depth) {
+            None => println!("[{}/{}] ok {}", i + 1, suite.len(), position.game.fen()),
+            Some(mismatch) => {
+                failed += 1;
+                println!("[{}/{}] FAILED\n{}\n", i + 1, suite.len(), mismatch);
+
+                let Some(engine) = engine else {
+                    continue;
+                };
+
+                match reference_divide(engine, &mismatch) {
+                    Ok(reference) => {
+                        println!("Difference from {}:", engine);
+                        for diff in mismatch.diff(&reference) {
+                            println!("{}", diff);
+                        }
+                        println!();
+                    }
+                    Err(error) => eprintln!("Couldn't run {}: {}", engine, error),
+                }
+            }
+        }
+    }
+
+    println!(
+        "{} of {} positions passed",
+        suite.len() - failed,
+        suite.len()
+    );
+
+    if failed > 0 {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,594 @@
+//! Attack tables for every piece
+//!
+//! Knights, kings and pawns attack the same squares no matter what else is on the board, so their
+//! attacks are stored in tables computed at compile time. Rooks and bishops are looked up in
+//! magic bitboard tables: the blockers on a slider's rays are multiplied with a magic number, and
+//! the top bits of the product are a unique index into a table of precomputed attacks.
+
+use std::sync::OnceLock;
+
+use crate::{Bitboard, Board, Color, Piece, PieceType, Square};
+
+const ROOK_DIRS: [(i32, i32); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const BISHOP_DIRS: [(i32, i32); 4] = [(1, 1), (-1, 1), (1, -1), (-1, -1)];
+const KING_DIRS: [(i32, i32); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (-1, 1),
+    (1, -1),
+    (-1, -1),
+];
+const KNIGHT_DIRS: [(i32, i32); 8] = [
+    (2, 1),
+    (2, -1),
+    (-2, 1),
+    (-2, -1),
+    (1, 2),
+    (1, -2),
+    (-1, 2),
+    (-1, -2),
+];
+
+/// Magic numbers for rooks, found with a brute force search for this board's square order
+const ROOK_MAGICS: [u64; 64] = [
+    0x2080002080400010,
+    0x00c0002001401000,
+    0x2100110008402002,
+    0x0880080081041000,
+    0x0200020020041008,
+    0x2300040008010012,
+    0x0c00283004008201,
+    0x0180010000407a80,
+    0x0168800080400020,
+    0x0010400040201000,
+    0x1001002001001048,
+    0x1001002408100100,
+    0x0801000408010012,
+    0x4001000209000400,
+    0x08a20004c8020001,
+    0x2002801145002280,
+    0x0080860021004200,
+    0x001000c009402002,
+    0x00b0002004002800,
+    0x100a808010020800,
+    0x8101010008000410,
+    0x0244008002000480,
+    0x0000040010810208,
+    0x2000020000448534,
+    0x4104400480008033,
+    0x0000810100204000,
+    0x0440430900200010,
+    0x4600240900100100,
+    0x0060080080040080,
+    0x0001000300080400,
+    0x0004084400011002,
+    0x0023040200008041,
+    0x0580050043002080,
+    0x0400804002802008,
+    0x0001002001004010,
+    0x1000200901001000,
+    0x4410800801800c00,
+    0xa012003806001004,
+    0x0020100104008802,
+    0x0004808402000041,
+    0x0010400170898000,
+    0x0080500020004004,
+    0x1040408012020020,
+    0x8010040008004040,
+    0x2001080100110004,
+    0x0000020004008080,
+    0x0021010810040002,
+    0x0800008c43020024,
+    0x0000800021005100,
+    0x0070201040008080,
+    0x0000d04282006a00,
+    0x0010014400080240,
+    0x0001080110050100,
+    0x0012000810240600,
+    0x0402000801040200,
+    0x028100108a004100,
+    0x0050800300102045,
+    0x8208210040120882,
+    0x8010600101183441,
+    0x020b000910006045,
+    0x0241001002480005,
+    0x0081000400880241,
+    0x0000009008024124,
+    0x0048122980410402,
+];
+
+/// Magic numbers for bishops, found with a brute force search for this board's square order
+const BISHOP_MAGICS: [u64; 64] = [
+    0x0848020822040013,
+    0x8010a40085821200,
+    0x0008008430840822,
+    0x0808048108040000,
+    0x1304042100008104,
+    0x5001012010204023,
+    0x81048801b8200420,
+    0x200a008084012000,
+    0x0040102001042084,
+    0x840a505042428020,
+    0x0000700102202920,
+    0x44101c0c10800002,
+    0x0040040422000000,
+    0x0180020802090202,
+    0x4020020811041202,
+    0x000104308c042000,
+    0x4140661002424400,
+    0x0028012008010460,
+    0x0188062102002a00,
+    0x0014004840102008,
+    0x0105000290400002,
+    0x8001022200410400,
+    0x104a041918013446,
+    0x008a000082008238,
+    0x04a0060008100430,
+    0x0008220008820801,
+    0x2508041208005010,
+    0x4008080200202020,
+    0x2441001013004000,
+    0x0030008060407000,
+    0x4008108000420800,
+    0x0012021050290100,
+    0x0210080482200500,
+    0xcc01112048100480,
+    0x0020402806500440,
+    0x00048e0080580080,
+    0x0040102020020080,
+    0x0028010440080807,
+    0x4601041108008800,
+    0x8040810e04104200,
+    0x901210110400088a,
+    0xa003080212081050,
+    0x00c1004048401004,
+    0x900000a014400800,
+    0x0008021040405401,
+    0x4020008206002090,
+    0x0004190424030100,
+    0x0424008a02026250,
+    0x8004088250900040,
+    0x1c00430088a04200,
+    0x0001020094040001,
+    0x8040210020880061,
+    0x2010040450442032,
+    0x0800840850044001,
+    0x0004040802140004,
+    0x0004080a04222020,
+    0x8088802110022000,
+    0x1081a10416114400,
+    0x0205010a24060820,
+    0x0000000720411080,
+    0x1008000208430400,
+    0x580c026028810840,
+    0x802020441020a110,
+    0x12c0022401020018,
+];
+
+/// Returns the squares one step away from a square in every direction
+const fn step_attacks<const N: usize>(dirs: [(i32, i32); N]) -> [Bitboard; 64] {
+    let mut table = [Bitboard::EMPTY; 64];
+
+    let mut square = 0;
+    while square < 64 {
+        let x = (square % 8) as i32;
+        let y = (square / 8) as i32;
+
+        let mut i = 0;
+        while i < N {
+            let c_x = x + dirs[i].0;
+            let c_y = y + dirs[i].1;
+
+            if c_x >= 0 && c_x < 8 && c_y >= 0 && c_y < 8 {
+                table[square].0 |= 1 << (c_y * 8 + c_x);
+            }
+
+            i += 1;
+        }
+
+        square += 1;
+    }
+
+    table
+}
+
+const KNIGHT_ATTACKS: [Bitboard; 64] = step_attacks(KNIGHT_DIRS);
+const KING_ATTACKS: [Bitboard; 64] = step_attacks(KING_DIRS);
+/// Pawn attacks indexed by `Color as usize`, white pawns move towards `y = 0`
+const PAWN_ATTACKS: [[Bitboard; 64]; 2] = [
+    step_attacks([(-1, -1), (1, -1)]),
+    step_attacks([(-1, 1), (1, 1)]),
+];
+
+/// Builds a table of the squares on the line between every pair of squares
+///
+/// # Arguments
+/// * `whole_line` - If true the whole line across the board is stored, including both squares.
+///   Otherwise only the squares strictly between the two squares are stored.
+const fn line_table(whole_line: bool) -> [[Bitboard; 64]; 64] {
+    let mut table = [[Bitboard::EMPTY; 64]; 64];
+
+    let mut square = 0;
+    while square < 64 {
+        let x = (square % 8) as i32;
+        let y = (square / 8) as i32;
+
+        let mut i = 0;
+        while i < KING_DIRS.len() {
+            let (d_x, d_y) = KING_DIRS[i];
+
+            // Walk backwards to the edge, then forwards over the whole line
+            let (mut c_x, mut c_y) = (x, y);
+            while c_x - d_x >= 0 && c_x - d_x < 8 && c_y - d_y >= 0 && c_y - d_y < 8 {
+                c_x -= d_x;
+                c_y -= d_y;
+            }
+
+            let mut line = 0;
+            while c_x >= 0 && c_x < 8 && c_y >= 0 && c_y < 8 {
+                line |= 1 << (c_y * 8 + c_x);
+                c_x += d_x;
+                c_y += d_y;
+            }
+
+            let mut between = 0;
+            let (mut c_x, mut c_y) = (x + d_x, y + d_y);
+            while c_x >= 0 && c_x < 8 && c_y >= 0 && c_y < 8 {
+                let other = (c_y * 8 + c_x) as usize;
+
+                table[square][other] = Bitboard(if whole_line { line } else { between });
+
+                between |= 1 << other;
+                c_x += d_x;
+                c_y += d_y;
+            }
+
+            i += 1;
+        }
+
+        square += 1;
+    }
+
+    table
+}
+
+static LINES: [[Bitboard; 64]; 64] = line_table(true);
+static BETWEEN: [[Bitboard; 64]; 64] = line_table(false);
+
+/// Walks the rays from a square until they hit a piece or the edge of the board
+///
+/// # Arguments
+/// * `square` - The index of the square
+/// * `occupancy` - The squares with pieces on them
+/// * `dirs` - The directions of the rays
+/// * `edges` - If true the last square of every ray is left out, which gives the relevant
+///   blocker mask of a magic
+fn slide(square: usize, occupancy: u64, dirs: &[(i32, i32)], edges: bool) -> u64 {
+    let x = (square % 8) as i32;
+    let y = (square / 8) as i32;
+    let on_board = |x: i32, y: i32| (0..8).contains(&x) && (0..8).contains(&y);
+
+    let mut attacks = 0;
+
+    for (d_x, d_y) in dirs {
+        let (mut c_x, mut c_y) = (x + d_x, y + d_y);
+
+        while on_board(c_x, c_y) && (!edges || on_board(c_x + d_x, c_y + d_y)) {
+            let bit = 1 << (c_y * 8 + c_x);
+            attacks |= bit;
+
+            if occupancy & bit != 0 {
+                break;
+            }
+
+            c_x += d_x;
+            c_y += d_y;
+        }
+    }
+
+    attacks
+}
+
+/// Everything needed to look up the attacks of a slider on one square
+struct Magic {
+    /// The squares where a blocker changes the attacks
+    mask: u64,
+    magic: u64,
+    shift: u32,
+    /// Where the attacks of this square start in the shared table
+    offset: usize,
+}
+
+impl Magic {
+    fn index(&self, occupancy: Bitboard) -> usize {
+        self.offset + ((occupancy.0 & self.mask).wrapping_mul(self.magic) >> self.shift) as usize
+    }
+}
+
+/// The magic lookup tables of rooks and bishops
+struct SliderTables {
+    rook: Vec<Magic>,
+    bishop: Vec<Magic>,
+    attacks: Vec<Bitboard>,
+}
+
+impl SliderTables {
+    fn new() -> SliderTables {
+        let mut attacks = vec![];
+        let rook = Self::fill(&mut attacks, &ROOK_DIRS, &ROOK_MAGICS);
+        let bishop = Self::fill(&mut attacks, &BISHOP_DIRS, &BISHOP_MAGICS);
+
+        SliderTables {
+            rook,
+            bishop,
+            attacks,
+        }
+    }
+
+    /// Adds the attacks of a slider on every square to the shared table
+    fn fill(attacks: &mut Vec<Bitboard>, dirs: &[(i32, i32)], magics: &[u64; 64]) -> Vec<Magic> {
+        (0..64)
+            .map(|square| {
+                let mask = slide(square, 0, dirs, true);
+                let magic = Magic {
+                    mask,
+                    magic: magics[square],
+                    shift: 64 - mask.count_ones(),
+                    offset: attacks.len(),
+                };
+
+                attacks.resize(attacks.len() + (1 << mask.count_ones()), Bitboard::EMPTY);
+
+                // Loop through every subset of the mask with the carry rippler trick
+                let mut blockers = 0u64;
+                loop {
+                    let index = magic.index(Bitboard(blockers));
+                    let slider_attacks = Bitboard(slide(square, blockers, dirs, false));
+
+                    debug_assert!(
+                        attacks[index].is_empty() || attacks[index] == slider_attacks,
+                        "The magic of square {} has a bad collision",
+                        square
+                    );
+                    attacks[index] = slider_attacks;
+
+                    blockers = blockers.wrapping_sub(mask) & mask;
+                    if blockers == 0 {
+                        break;
+                    }
+                }
+
+                magic
+            })
+            .collect()
+    }
+}
+
+/// Returns the slider tables, they are built the first time they are needed
+fn slider_tables() -> &'static SliderTables {
+    static TABLES: OnceLock<SliderTables> = OnceLock::new();
+
+    TABLES.get_or_init(SliderTables::new)
+}
+
+/// Returns the index of a square
+fn index(square: (usize, usize)) -> usize {
+    debug_assert!(
+        square.0 < 8 && square.1 < 8,
+        "x and y must be between 0 and 7"
+    );
+
+    square.1 * 8 + square.0
+}
+
+/// Returns the squares a rook attacks
+pub(crate) fn rook_attacks(square: (usize, usize), occupancy: Bitboard) -> Bitboard {
+    let tables = slider_tables();
+
+    tables.attacks[tables.rook[index(square)].index(occupancy)]
+}
+
+/// Returns the squares a bishop attacks
+pub(crate) fn bishop_attacks(square: (usize, usize), occupancy: Bitboard) -> Bitboard {
+    let tables = slider_tables();
+
+    tables.attacks[tables.bishop[index(square)].index(occupancy)]
+}
+
+/// Returns the squares a knight attacks
+pub(crate) fn knight_attacks(square: (usize, usize)) -> Bitboard {
+    KNIGHT_ATTACKS[index(square)]
+}
+
+/// Returns the squares a king attacks
+pub(crate) fn king_attacks(square: (usize, usize)) -> Bitboard {
+    KING_ATTACKS[index(square)]
+}
+
+/// Returns the squares a pawn attacks diagonally
+pub(crate) fn pawn_attacks(square: (usize, usize), color: Color) -> Bitboard {
+    PAWN_ATTACKS[color as usize][index(square)]
+}
+
+/// Returns the whole line across the board through two squares, or an empty bitboard if the
+/// squares aren't on the same rank, file or diagonal
+pub(crate) fn line(a: (usize, usize), b: (usize, usize)) -> Bitboard {
+    LINES[index(a)][index(b)]
+}
+
+/// Returns the squares strictly between two squares, or an empty bitboard if the squares aren't
+/// on the same rank, file or diagonal
+pub(crate) fn between(a: (usize, usize), b: (usize, usize)) -> Bitboard {
+    BETWEEN[index(a)][index(b)]
+}
+
+/// Returns the squares a piece attacks
+///
+/// A square is attacked if the piece could capture an enemy piece on it. The rays of sliding
+/// pieces stop at the first occupied square, which is included. Pawns only attack diagonally, and
+/// castling is never an attack.
+///
+/// # Arguments
+/// * `square` - The square of the piece
+/// * `piece` - The piece, the color only matters for pawns
+/// * `occupancy` - The squares with pieces on them
+///
+/// # Returns
+/// * `Bitboard` - The attacked squares
+///
+/// # Examples
+/// ```
+/// use fritiofr_chess::{attacks_from, Bitboard, Color, Game, Piece, PieceType, Square};
+///
+/// let game = Game::start_pos();
+/// let occupancy = game.get_board().occupied();
+/// let rook = Piece {
+///     piece_type: PieceType::Rook,
+///     color: Color::White,
+/// };
+///
+/// // The rook on a1 is blocked by the knight on b1 and the pawn on a2
+/// let attacks = attacks_from("a1".parse::<Square>().unwrap(), rook, occupancy);
+/// assert_eq!(attacks, Bitboard::from_square((1, 7)) | Bitboard::from_square((0, 6)));
+/// ```
+pub fn attacks_from(square: Square, piece: Piece, occupancy: Bitboard) -> Bitboard {
+    piece_attacks(square.into(), piece, occupancy)
+}
+
+/// Returns the squares a piece attacks, the same as `attacks_from` but with coordinates
+pub(crate) fn piece_attacks(square: (usize, usize), piece: Piece, occupancy: Bitboard) -> Bitboard {
+    match piece.piece_type {
+        PieceType::Pawn => pawn_attacks(square, piece.color),
+        PieceType::Knight => knight_attacks(square),
+        PieceType::Bishop => bishop_attacks(square, occupancy),
+        PieceType::Rook => rook_attacks(square, occupancy),
+        PieceType::Queen => rook_attacks(square, occupancy) | bishop_attacks(square, occupancy),
+        PieceType::King => king_attacks(square),
+    }
+}
+
+impl Board {
+    /// Returns the pieces of a color that attack a square
+    ///
+    /// # Arguments
+    /// * `square` - The coordinates of the square
+    /// * `color` - The color of the attacking pieces
+    pub(crate) fn attackers(&self, square: (usize, usize), color: Color) -> Bitboard {
+        let occupancy = self.occupied();
+        let rooks = self.type_pieces(PieceType::Rook) | self.type_pieces(PieceType::Queen);
+        let bishops = self.type_pieces(PieceType::Bishop) | self.type_pieces(PieceType::Queen);
+
+        // A piece attacks the square if the same piece on the square would attack it back, pawns
+        // have to look in the direction of the other color
+        let attackers = (pawn_attacks(square, color.opposite())
+            & self.type_pieces(PieceType::Pawn))
+            | (knight_attacks(square) & self.type_pieces(PieceType::Knight))
+            | (king_attacks(square) & self.type_pieces(PieceType::King))
+            | (rook_attacks(square, occupancy) & rooks)
+            | (bishop_attacks(square, occupancy) & bishops);
+
+        attackers & self.color_pieces(color)
+    }
+
+    /// Returns if any piece of a color attacks a square
+    ///
+    /// # Arguments
+    /// * `square` - The coordinates of the square
+    /// * `color` - The color of the attacking pieces
+    pub(crate) fn is_attacked(&self, square: (usize, usize), color: Color) -> bool {
+        !self.attackers(square, color).is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn sliders_should_match_ray_stepping() {
+        let fens_to_test = vec![
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8",
+            "8/8/8/8/8/8/8/8",
+        ];
+
+        for fen in fens_to_test {
+            let occupancy = Board::from_fen(fen).unwrap().occupied();
+
+            for square in 0..64 {
+                let pos = (square % 8, square / 8);
+
+                assert_eq!(
+                    rook_attacks(pos, occupancy).0,
+                    slide(square, occupancy.0, &ROOK_DIRS, false)
+                );
+                assert_eq!(
+                    bishop_attacks(pos, occupancy).0,
+                    slide(square, occupancy.0, &BISHOP_DIRS, false)
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_find_lines() {
+        // a8 and h1 are on the long diagonal
+        assert_eq!(line((0, 0), (7, 7)).count(), 8);
+        assert_eq!(between((0, 0), (7, 7)).count(), 6);
+        assert!(between((0, 0), (7, 7)).contains((3, 3)));
+
+        // e1 and e4 are on the e-file
+        assert_eq!(line((4, 7), (4, 4)).count(), 8);
+        assert_eq!(
+            between((4, 7), (4, 4)),
+            Bitboard::from_square((4, 6)) | Bitboard::from_square((4, 5))
+        );
+
+        // Neighbours have nothing between them, and a knight jump isn't a line
+        assert!(between((4, 7), (4, 6)).is_empty());
+        assert!(line((1, 7), (2, 5)).is_empty());
+    }
+
+    #[test]
+    pub fn attacks_from_should_not_wrap_around_the_board() {
+        let knight = Piece {
+            piece_type: PieceType::Knight,
+            color: Color::White,
+        };
+
+        // A knight on h7 only attacks squares on the f and g files
+        let attacks = attacks_from("h7".parse().unwrap(), knight, Bitboard(0));
+        assert_eq!(attacks, knight_attacks((7, 1)));
+        assert_eq!(attacks.count(), 3);
+
+        // Coordinates outside of the board can't be made into a square
+        assert!(Square::try_from((8, 0)).is_err());
+        assert!(Square::try_from((80, 0)).is_err());
+    }
+
+    #[test]
+    pub fn should_find_step_attacks() {
+        // Knight on b1
+        assert_eq!(
+            knight_attacks((1, 7)),
+            Bitboard::from_square((0, 5))
+                | Bitboard::from_square((2, 5))
+                | Bitboard::from_square((3, 6))
+        );
+        // King on h8
+        assert_eq!(king_attacks((7, 0)).count(), 3);
+        // White pawn on e4 and black pawn on a5
+        assert_eq!(
+            pawn_attacks((4, 4), Color::White),
+            Bitboard::from_square((3, 3)) | Bitboard::from_square((5, 3))
+        );
+        assert_eq!(
+            pawn_attacks((0, 3), Color::Black),
+            Bitboard::from_square((1, 4))
+        );
+    }
+}
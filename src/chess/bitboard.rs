@@ -0,0 +1,130 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use crate::Square;
+
+/// A set of squares stored as the bits of a `u64`
+///
+/// The square `(x, y)` is bit `y * 8 + x`, the same order as the tiles of a `Board`. Since the
+/// board is stored with rank 8 at `y = 0`, bit 0 is a8 and bit 63 is h1.
+///
+/// # Examples
+/// ```
+/// use fritiofr_chess::{Bitboard, Board, Color, PieceType};
+///
+/// let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR").unwrap();
+/// let knights = board.pieces(Color::White, PieceType::Knight);
+///
+/// assert_eq!(knights.count(), 2);
+/// assert!(knights.contains((1, 7)));
+/// assert_eq!(knights.squares().collect::<Vec<_>>(), vec![(1, 7), (6, 7)]);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Bitboard(pub u64);
+
+impl Bitboard {
+    /// A bitboard without any squares
+    pub const EMPTY: Bitboard = Bitboard(0);
+    /// A bitboard with every square
+    pub const FULL: Bitboard = Bitboard(u64::MAX);
+
+    /// Returns a bitboard with only one square
+    ///
+    /// # Arguments
+    /// * `pos` - The coordinates of the square
+    pub const fn from_square(pos: (usize, usize)) -> Bitboard {
+        Bitboard(1 << (pos.1 * 8 + pos.0))
+    }
+
+    /// Returns if a square is in the set
+    ///
+    /// # Arguments
+    /// * `pos` - The coordinates of the square
+    pub const fn contains(&self, pos: (usize, usize)) -> bool {
+        self.0 & (1 << (pos.1 * 8 + pos.0)) != 0
+    }
+
+    /// Returns if there are no squares in the set
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns the number of squares in the set
+    pub const fn count(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Returns the first square of the set, the one with the lowest index
+    pub const fn first(&self) -> Option<(usize, usize)> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let i = self.0.trailing_zeros() as usize;
+
+        Some((i % 8, i / 8))
+    }
+
+    /// Returns an iterator over all squares in the set, ordered by index
+    pub fn squares(&self) -> Squares {
+        Squares(self.0)
+    }
+}
+
+impl From<Square> for Bitboard {
+    fn from(square: Square) -> Self {
+        Bitboard(1 << square.index())
+    }
+}
+
+/// Iterator over the squares of a `Bitboard`
+pub struct Squares(u64);
+
+impl Iterator for Squares {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let square = Bitboard(self.0).first()?;
+
+        // Clear the lowest bit
+        self.0 &= self.0 - 1;
+
+        Some(square)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.0.count_ones() as usize;
+        (count, Some(count))
+    }
+}
+
+impl ExactSizeIterator for Squares {}
+
+macro_rules! bit_op {
+    ($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident) => {
+        impl $op for Bitboard {
+            type Output = Bitboard;
+
+            fn $fn(self, rhs: Bitboard) -> Bitboard {
+                Bitboard($op::$fn(self.0, rhs.0))
+            }
+        }
+
+        impl $op_assign for Bitboard {
+            fn $fn_assign(&mut self, rhs: Bitboard) {
+                $op_assign::$fn_assign(&mut self.0, rhs.0)
+            }
+        }
+    };
+}
+
+bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign);
+bit_op!(BitOr, bitor, BitOrAssign, bitor_assign);
+bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+impl Not for Bitboard {
+    type Output = Bitboard;
+
+    fn not(self) -> Bitboard {
+        Bitboard(!self.0)
+    }
+}
@@ -1,9 +1,16 @@
-use crate::{error::FromFenError, Color, Piece, PieceType};
+use crate::{
+    error::{FenErrorKind, FenField, FromFenError},
+    Bitboard, Color, Piece, PieceType, Square,
+};
 
 /// A chess board
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct Board {
     pub(crate) tiles: [Option<Piece>; 64],
+    /// The squares of every color, indexed by `Color as usize`
+    by_color: [Bitboard; 2],
+    /// The squares of every piece type, indexed by `PieceType as usize`
+    by_type: [Bitboard; 6],
 }
 
 impl Board {
@@ -20,50 +27,92 @@ impl Board {
     /// Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR");
     /// ```
     pub fn from_fen(fen: &str) -> Result<Board, FromFenError> {
-        let mut tiles: [Option<Piece>; 64] = [None; 64];
-
-        let rows = fen.split('/').collect::<Vec<&str>>();
-
-        if rows.len() != 8 {
-            return Err(FromFenError::IncorrectAmountOfSlash);
-        }
-
-        let mut i = 0;
-        for (row_index, row) in rows.iter().enumerate() {
-            for c in row.chars() {
-                let parsed_value = c.to_string().parse::<usize>();
+        let mut board = Board {
+            tiles: [None; 64],
+            by_color: [Bitboard::EMPTY; 2],
+            by_type: [Bitboard::EMPTY; 6],
+        };
+
+        let error = |kind, offset| FromFenError {
+            kind,
+            field: FenField::Pieces,
+            offset,
+        };
+
+        let mut x = 0;
+        let mut y = 0;
+
+        for (offset, c) in fen.char_indices() {
+            if c == '/' {
+                if x != 8 {
+                    return Err(error(FenErrorKind::IncorrectAmountOfTiles, offset));
+                }
+                if y == 7 {
+                    return Err(error(FenErrorKind::IncorrectAmountOfSlash, offset));
+                }
 
-                if i >= row_index * 8 + 8 {
-                    return Err(FromFenError::IncorrectAmountOfTiles);
+                x = 0;
+                y += 1;
+            } else if let Some(n) = c.to_digit(10) {
+                if n == 0 || n == 9 {
+                    return Err(error(FenErrorKind::UnknownCharacter, offset));
                 }
 
-                if let Ok(n) = parsed_value {
-                    i += n;
-                } else {
-                    let piece = Piece::try_from(c).map_err(|_| FromFenError::UnknownCharacter)?;
+                x += n as usize;
 
-                    tiles[i] = Some(piece);
+                if x > 8 {
+                    return Err(error(FenErrorKind::IncorrectAmountOfTiles, offset));
+                }
+            } else {
+                let piece = Piece::try_from(c)
+                    .map_err(|_| error(FenErrorKind::UnknownCharacter, offset))?;
 
-                    i += 1;
+                if x == 8 {
+                    return Err(error(FenErrorKind::IncorrectAmountOfTiles, offset));
                 }
+
+                board.set_tile(x, y, piece);
+                x += 1;
             }
         }
 
-        if i != 64 {
-            return Err(FromFenError::IncorrectAmountOfTiles);
+        if y != 7 {
+            return Err(error(FenErrorKind::IncorrectAmountOfSlash, fen.len()));
+        }
+        if x != 8 {
+            return Err(error(FenErrorKind::IncorrectAmountOfTiles, fen.len()));
         }
 
-        Ok(Board { tiles })
+        Ok(board)
     }
 
     /// Returns the position of the king of a color
     pub fn get_king_pos(&self, color: Color) -> Option<(usize, usize)> {
-        (0..64).map(|i| (i % 8, i / 8)).find(|(x, y)| {
-            if let Some(piece) = self.get_tile(*x, *y) {
-                return piece.color == color && piece.piece_type == PieceType::King;
-            }
-            false
-        })
+        self.pieces(color, PieceType::King).first()
+    }
+
+    /// Returns the squares of all pieces of a color and a type
+    ///
+    /// # Arguments
+    /// * `color` - The color of the pieces
+    /// * `piece_type` - The type of the pieces
+    pub fn pieces(&self, color: Color, piece_type: PieceType) -> Bitboard {
+        self.by_color[color as usize] & self.by_type[piece_type as usize]
+    }
+
+    /// Returns the squares of all pieces of a color
+    pub fn color_pieces(&self, color: Color) -> Bitboard {
+        self.by_color[color as usize]
+    }
+
+    /// Returns the squares of all pieces of a type, no matter the color
+    pub fn type_pieces(&self, piece_type: PieceType) -> Bitboard {
+        self.by_type[piece_type as usize]
+    }
+
+    /// Returns the squares of all pieces on the board
+    pub fn occupied(&self) -> Bitboard {
+        self.by_color[0] | self.by_color[1]
     }
 
     /// Returns a piece on the board
@@ -83,9 +132,14 @@ impl Board {
             panic!("x and y must be between 0 and 7");
         }
 
+        self.remove_tile(x, y);
+
         let index = y * 8 + x;
+        let bit = Bitboard::from_square((x, y));
 
         self.tiles[index] = Some(piece);
+        self.by_color[piece.color as usize] |= bit;
+        self.by_type[piece.piece_type as usize] |= bit;
     }
 
     /// Removes a tile from the board
@@ -96,9 +150,49 @@ impl Board {
 
         let index = y * 8 + x;
 
+        if let Some(piece) = self.tiles[index] {
+            let bit = !Bitboard::from_square((x, y));
+
+            self.by_color[piece.color as usize] &= bit;
+            self.by_type[piece.piece_type as usize] &= bit;
+        }
+
         self.tiles[index] = None;
     }
 
+    /// Returns the piece on a square
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Board, Color, PieceType};
+    ///
+    /// let board = Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR").unwrap();
+    /// let piece = board.piece_at("d1".parse().unwrap()).unwrap();
+    ///
+    /// assert_eq!(piece.piece_type, PieceType::Queen);
+    /// assert_eq!(piece.color, Color::White);
+    /// ```
+    pub fn piece_at(&self, square: Square) -> Option<Piece> {
+        self.tiles[square.index()]
+    }
+
+    /// Puts a piece on a square, replacing the piece that was there
+    pub fn set_piece_at(&mut self, square: Square, piece: Piece) {
+        self.set_tile(square.x(), square.y(), piece);
+    }
+
+    /// Removes the piece on a square
+    pub fn remove_piece_at(&mut self, square: Square) {
+        self.remove_tile(square.x(), square.y());
+    }
+
+    /// Returns the square of the king of a color
+    pub fn king_square(&self, color: Color) -> Option<Square> {
+        self.pieces(color, PieceType::King)
+            .first()
+            .and_then(|pos| Square::try_from(pos).ok())
+    }
+
     /// Returns the board as a FEN string
     pub fn fen(&self) -> String {
         let mut fen = String::new();
@@ -137,23 +231,13 @@ impl Board {
     }
 }
 
-impl Eq for Board {}
-impl PartialEq for Board {
-    fn eq(&self, other: &Self) -> bool {
-        self.tiles
-            .into_iter()
-            .zip(other.tiles.into_iter())
-            .all(|(a, b)| a == b)
-    }
-}
-
 impl std::fmt::Display for Board {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut game_string = String::new();
 
         for (i, tile) in self.tiles.iter().enumerate() {
             if i % 8 == 0 && i != 0 {
-                game_string.push_str("\n");
+                game_string.push('\n');
             }
 
             if let Some(piece) = tile {
@@ -198,4 +282,50 @@ mod tests {
         assert_eq!(Some((4, 3)), board.get_king_pos(Color::Black));
         assert_eq!(Some((2, 6)), board.get_king_pos(Color::White));
     }
+
+    #[test]
+    pub fn bitboards_should_follow_tiles() {
+        let mut board =
+            Board::from_fen("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1").unwrap();
+
+        let white_knight = Piece {
+            piece_type: PieceType::Knight,
+            color: Color::White,
+        };
+        let black_queen = Piece {
+            piece_type: PieceType::Queen,
+            color: Color::Black,
+        };
+
+        board.set_tile(7, 2, black_queen);
+        board.remove_tile(5, 5);
+        board.set_tile(0, 0, white_knight);
+
+        for color in [Color::White, Color::Black] {
+            for piece_type in [
+                PieceType::Pawn,
+                PieceType::Knight,
+                PieceType::Bishop,
+                PieceType::Rook,
+                PieceType::Queen,
+                PieceType::King,
+            ] {
+                let piece = Piece { piece_type, color };
+                let expected = (0..64)
+                    .filter(|i| board.get_tile(i % 8, i / 8) == Some(piece))
+                    .fold(0, |bb, i| bb | 1 << i);
+
+                assert_eq!(board.pieces(color, piece_type), Bitboard(expected));
+            }
+        }
+
+        assert_eq!(
+            board.occupied().count(),
+            board.tiles.iter().filter(|t| t.is_some()).count() as u32
+        );
+        assert_eq!(
+            board.pieces(Color::White, PieceType::Knight),
+            Bitboard::from_square((0, 0))
+        );
+    }
 }
@@ -0,0 +1,365 @@
+//! Reading and running perft test suites in Extended Position Description (EPD)
+//!
+//! A perft suite like the classic `perftsuite.epd` has one position per line, the FEN is followed
+//! by the expected node counts, e.g. `<fen> ;D1 20 ;D2 400 ;D3 8902`.
+//!
+//! The suite only has the total count of every depth, so a mismatch is diffed move by move
+//! against the divide of a reference engine, e.g. the output of Stockfish's `go perft`.
+
+use std::fmt;
+
+use crate::{error::ParseEpdError, Game, Move};
+
+/// A position from a perft suite together with its expected node counts
+#[derive(Debug, Clone)]
+pub struct PerftPosition {
+    /// The line in the EPD string, starting at 1
+    pub line: usize,
+    /// The position
+    pub game: Game,
+    /// The expected perft for every depth in the line, in the order they were written
+    pub counts: Vec<(u8, u64)>,
+}
+
+/// A depth where the move generator doesn't find the expected amount of nodes
+#[derive(Debug, Clone)]
+pub struct PerftMismatch {
+    /// The line of the position in the EPD string
+    pub line: usize,
+    /// The FEN of the position
+    pub fen: String,
+    /// The depth with the wrong count
+    pub depth: u8,
+    /// The count from the suite
+    pub expected: u64,
+    /// The count from `Game::perft`
+    pub found: u64,
+    /// The divide of the position at `depth`, to diff against another engine
+    pub divide: Vec<(Move, u64)>,
+}
+
+/// A move where the divide differs from the divide of a reference engine
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DivideDiff {
+    /// The move in UCI notation
+    pub mv: String,
+    /// The count from `Game::divide`, or None if we don't generate the move
+    pub found: Option<u64>,
+    /// The count from the reference engine, or None if it doesn't generate the move
+    pub expected: Option<u64>,
+}
+
+/// Reads all positions in a perft suite
+///
+/// Empty lines and lines starting with `#` are skipped. The FEN can have four or six fields.
+///
+/// # Arguments
+/// * `epd` - The EPD string, one position per line
+///
+/// # Returns
+/// * `Result<Vec<PerftPosition>, ParseEpdError>` - All positions in the string, or an error with
+///   the line that couldn't be read
+///
+/// # Examples
+/// ```
+/// use fritiofr_chess::{epd::read_perft_suite, Game};
+///
+/// let suite = read_perft_suite(
+///     "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - ;D1 20 ;D2 400 ;D3 8902",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(suite[0].game, Game::start_pos());
+/// assert_eq!(suite[0].counts, vec![(1, 20), (2, 400), (3, 8902)]);
+/// ```
+pub fn read_perft_suite(epd: &str) -> Result<Vec<PerftPosition>, ParseEpdError> {
+    let mut positions = vec![];
+
+    for (i, text) in epd.lines().enumerate() {
+        let line = i + 1;
+        let text = text.trim();
+
+        if text.is_empty() || text.starts_with('#') {
+            continue;
+        }
+
+        let mut operations = text.split(';');
+        let fen = operations
+            .next()
+            .expect("Split always returns a first part");
+
+        let game = Game::from_fen_lenient(fen.trim())
+            .map_err(|source| ParseEpdError::InvalidFen { line, source })?;
+
+        let counts = operations
+            .map(|operation| {
+                perft_count(operation.trim()).ok_or_else(|| ParseEpdError::InvalidPerftCount {
+                    line,
+                    operation: operation.trim().to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        positions.push(PerftPosition { line, game, counts });
+    }
+
+    Ok(positions)
+}
+
+/// Parses a perft operation like `D3 8902` into the depth and the count
+fn perft_count(operation: &str) -> Option<(u8, u64)> {
+    let (depth, count) = operation.strip_prefix('D')?.split_once(' ')?;
+
+    Some((depth.parse().ok()?, count.trim().parse().ok()?))
+}
+
+impl PerftPosition {
+    /// Runs perft for every depth of the position up to a max depth
+    ///
+    /// # Arguments
+    /// * `max_depth` - The deepest depth to check, deeper counts in the suite are skipped
+    ///
+    /// # Returns
+    /// * `Option<PerftMismatch>` - The first depth with the wrong count, or None if every count
+    ///   is right
+    pub fn check(&self, max_depth: u8) -> Option<PerftMismatch> {
+        self.counts
+            .iter()
+            .filter(|(depth, _)| *depth <= max_depth)
+            .find_map(|&(depth, expected)| {
+                let found = self.game.perft(depth);
+
+                (found != expected).then(|| PerftMismatch {
+                    line: self.line,
+                    fen: self.game.fen(),
+                    depth,
+                    expected,
+                    found,
+                    divide: self.game.divide(depth),
+                })
+            })
+    }
+}
+
+impl PerftMismatch {
+    /// Returns every move where the divide differs from the divide of a reference engine
+    ///
+    /// # Arguments
+    /// * `reference` - The divide of the reference engine at the same depth, e.g. from
+    ///   `parse_divide`
+    ///
+    /// # Returns
+    /// * `Vec<DivideDiff>` - The moves with different counts, the moves that only one side
+    ///   generates are included with None for the other side
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::epd::{parse_divide, read_perft_suite, run_perft_suite, DivideDiff};
+    ///
+    /// let suite = read_perft_suite("4k3/8/8/8/8/8/8/4K2R w K - ;D1 16").unwrap();
+    /// let mismatch = &run_perft_suite(&suite, 1)[0];
+    ///
+    /// // The reference engine only found two of the moves and one that we don't generate
+    /// let reference = parse_divide("e1g1: 1\ne1f1: 1\nh1h9: 1\n\nNodes searched: 3");
+    /// let diff = mismatch.diff(&reference);
+    ///
+    /// assert_eq!(diff.len(), 14);
+    /// assert!(diff.contains(&DivideDiff {
+    ///     mv: "h1h9".to_string(),
+    ///     found: None,
+    ///     expected: Some(1),
+    /// }));
+    /// assert!(diff.contains(&DivideDiff {
+    ///     mv: "h1h2".to_string(),
+    ///     found: Some(1),
+    ///     expected: None,
+    /// }));
+    /// ```
+    pub fn diff(&self, reference: &[(String, u64)]) -> Vec<DivideDiff> {
+        let found = self
+            .divide
+            .iter()
+            .map(|(mv, count)| {
+                let uci = mv.uci().expect("Generated moves are on the board");
+                (uci, *count)
+            })
+            .collect::<Vec<_>>();
+
+        let mut diffs = found
+            .iter()
+            .map(|(mv, count)| DivideDiff {
+                mv: mv.clone(),
+                found: Some(*count),
+                expected: reference
+                    .iter()
+                    .find(|(other, _)| other == mv)
+                    .map(|(_, count)| *count),
+            })
+            .chain(
+                reference
+                    .iter()
+                    .filter(|(mv, _)| found.iter().all(|(other, _)| other != mv))
+                    .map(|(mv, count)| DivideDiff {
+                        mv: mv.clone(),
+                        found: None,
+                        expected: Some(*count),
+                    }),
+            )
+            .filter(|diff| diff.found != diff.expected)
+            .collect::<Vec<_>>();
+
+        diffs.sort_by(|a, b| a.mv.cmp(&b.mv));
+        diffs
+    }
+}
+
+/// Parses a divide in the format of Stockfish's `go perft`
+///
+/// Every line like `e2e4: 20` is a move and its count, all other lines are skipped.
+///
+/// # Arguments
+/// * `output` - The output of the engine
+///
+/// # Returns
+/// * `Vec<(String, u64)>` - The moves in UCI notation and their counts
+pub fn parse_divide(output: &str) -> Vec<(String, u64)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (mv, count) = line.trim().split_once(':')?;
+
+            if mv.len() < 4 || mv.len() > 5 || !mv.chars().all(|c| c.is_ascii_alphanumeric()) {
+                return None;
+            }
+
+            Some((mv.to_string(), count.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+/// Runs a whole perft suite
+///
+/// # Arguments
+/// * `positions` - The positions of the suite, from `read_perft_suite`
+/// * `max_depth` - The deepest depth to check
+///
+/// # Returns
+/// * `Vec<PerftMismatch>` - The first wrong depth of every position that has one
+pub fn run_perft_suite(positions: &[PerftPosition], max_depth: u8) -> Vec<PerftMismatch> {
+    positions
+        .iter()
+        .filter_map(|position| position.check(max_depth))
+        .collect()
+}
+
+impl fmt::Display for PerftMismatch {
+    /// Writes the mismatch followed by the divide in the same format as Stockfish's `go perft`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Line {}: perft {} of \"{}\" is {}, expected {}",
+            self.line, self.depth, self.fen, self.found, self.expected
+        )?;
+
+        for (mv, count) in &self.divide {
+            writeln!(
+                f,
+                "{}: {}",
+                mv.uci().expect("Generated moves are on the board"),
+                count
+            )?;
+        }
+
+        writeln!(f)?;
+        write!(f, "Nodes searched: {}", self.found)
+    }
+}
+
+impl fmt::Display for DivideDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let count = |count: Option<u64>| count.map_or("-".to_string(), |c| c.to_string());
+
+        write!(
+            f,
+            "{}: {}, expected {}",
+            self.mv,
+            count(self.found),
+            count(self.expected)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUITE: &str = "\
+# A few positions from perftsuite.epd
+rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - ;D1 20 ;D2 400 ;D3 8902
+
+r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - ;D1 48 ;D2 2039 ;D3 97862
+4k3/8/8/8/8/8/8/4K2R w K - 0 1 ;D1 15 ;D2 66 ;D3 1197
+";
+
+    #[test]
+    pub fn should_read_perft_suites() {
+        let suite = read_perft_suite(SUITE).unwrap();
+
+        assert_eq!(suite.len(), 3);
+        assert_eq!(suite[1].line, 4);
+        assert_eq!(suite[1].counts, vec![(1, 48), (2, 2039), (3, 97862)]);
+        assert_eq!(suite[2].game.fen(), "4k3/8/8/8/8/8/8/4K2R w K - 0 1");
+
+        assert!(matches!(
+            read_perft_suite("8/8/8 w - - ;D1 1"),
+            Err(ParseEpdError::InvalidFen { line: 1, .. })
+        ));
+        assert!(matches!(
+            read_perft_suite("\n4k3/8/8/8/8/8/8/4K2R w K - ;D1"),
+            Err(ParseEpdError::InvalidPerftCount { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    pub fn should_report_mismatches() {
+        let suite = read_perft_suite(SUITE).unwrap();
+        assert!(run_perft_suite(&suite, 3).is_empty());
+
+        let suite = read_perft_suite("4k3/8/8/8/8/8/8/4K2R w K - ;D1 15 ;D2 67").unwrap();
+        let mismatches = run_perft_suite(&suite, 3);
+
+        assert_eq!(mismatches.len(), 1);
+        assert_eq!((mismatches[0].depth, mismatches[0].found), (2, 66));
+        assert_eq!(mismatches[0].divide.len(), 15);
+        let report = mismatches[0].to_string();
+        assert!(report.contains("\ne1g1: 3\n"));
+        assert!(report.ends_with("\n\nNodes searched: 66"));
+    }
+
+    #[test]
+    pub fn should_diff_against_a_reference_divide() {
+        let suite = read_perft_suite("4k3/8/8/8/8/8/8/4K2R w K - ;D2 67").unwrap();
+        let mismatch = &run_perft_suite(&suite, 2)[0];
+
+        // The reference counts one more reply to Rh2 and doesn't know about castling
+        let mut reference = mismatch
+            .divide
+            .iter()
+            .map(|(mv, count)| (mv.uci().unwrap(), *count))
+            .filter(|(mv, _)| mv != "e1g1")
+            .collect::<Vec<_>>();
+        reference.iter_mut().find(|(mv, _)| mv == "h1h2").unwrap().1 += 1;
+
+        let output = reference
+            .iter()
+            .map(|(mv, count)| format!("{}: {}\n", mv, count))
+            .collect::<String>()
+            + "\nNodes searched: 64\n";
+        let diff = mismatch.diff(&parse_divide(&output));
+
+        assert_eq!(
+            diff.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+            vec!["e1g1: 3, expected -", "h1h2: 5, expected 6"]
+        );
+    }
+}
@@ -1,12 +1,26 @@
+use crate::{Color, Square};
+
+/// An error from parsing a FEN string, with the field and byte offset where it was found
 #[derive(thiserror::Error, Debug)]
-pub enum FromFenError {
-    #[error("FEN string has too many or too few slashes")]
+#[error("{kind} in the {field} field at byte {offset}")]
+pub struct FromFenError {
+    /// What is wrong with the FEN string
+    pub kind: FenErrorKind,
+    /// The field that is wrong
+    pub field: FenField,
+    /// The byte offset in the FEN string where the problem is
+    pub offset: usize,
+}
+
+#[derive(thiserror::Error, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FenErrorKind {
+    #[error("Too many or too few slashes")]
     IncorrectAmountOfSlash,
-    #[error("Unknown character in fen string")]
+    #[error("Unknown character")]
     UnknownCharacter,
-    #[error("FEN string has too many or too few tiles")]
+    #[error("Too many or too few tiles")]
     IncorrectAmountOfTiles,
-    #[error("FEN string has too many or too few parts")]
+    #[error("Too many or too few parts")]
     IncorrectAmountOfParts,
     #[error("Unknown turn")]
     UnknownTurn,
@@ -16,12 +30,50 @@ pub enum FromFenError {
     IncorrectLength,
     #[error("Invalid en passant")]
     InvalidEnPassant,
+    #[error("Invalid halfmove clock")]
+    InvalidHalfmoveClock,
+    #[error("Invalid fullmove number")]
+    InvalidFullmoveNumber,
 }
 
-#[derive(thiserror::Error, Debug)]
+/// The six fields of a FEN string
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FenField {
+    Pieces,
+    Turn,
+    Castling,
+    EnPassant,
+    HalfmoveClock,
+    FullmoveNumber,
+}
+
+impl std::fmt::Display for FenField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            FenField::Pieces => "pieces",
+            FenField::Turn => "turn",
+            FenField::Castling => "castling",
+            FenField::EnPassant => "en passant",
+            FenField::HalfmoveClock => "halfmove clock",
+            FenField::FullmoveNumber => "fullmove number",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(thiserror::Error, Debug, Copy, Clone, PartialEq, Eq)]
 pub enum GameApplyMoveError {
-    #[error("The move is not valid for this game")]
-    InvalidMove,
+    #[error("There is no piece on {0}")]
+    NoPieceOnSquare(Square),
+    #[error("The piece belongs to the player that isn't to move")]
+    NotYourTurn,
+    #[error("The piece can't make that move")]
+    IllegalMove,
+    #[error("The move leaves the king in check")]
+    LeavesKingInCheck,
+    #[error("The game has already ended")]
+    GameOver,
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -33,3 +85,112 @@ pub enum ParsePieceError {
     #[error("Unknown character piece")]
     UnknownCharacterPiece,
 }
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseSanError {
+    #[error("Provided string is empty")]
+    Empty,
+    #[error("Provided string is not valid SAN")]
+    InvalidSyntax,
+    #[error("No legal move matches the SAN")]
+    IllegalMove,
+    #[error("More than one legal move matches the SAN")]
+    AmbiguousMove,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseUciError {
+    #[error("Provided string is not a valid UCI move")]
+    InvalidSyntax,
+    #[error("No legal move matches the UCI move")]
+    IllegalMove,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParsePgnError {
+    #[error("PGN string holds no games")]
+    NoGame,
+    #[error("Invalid tag pair")]
+    InvalidTag,
+    #[error("Unexpected character '{0}' in PGN string")]
+    UnexpectedCharacter(char),
+    #[error("Comment is never closed")]
+    UnterminatedComment,
+    #[error("Variation parenthesis are not balanced")]
+    UnbalancedVariation,
+    #[error("Invalid FEN tag")]
+    InvalidFen(#[from] FromFenError),
+    #[error("Illegal move \"{san}\" at ply {ply}")]
+    IllegalMove {
+        san: String,
+        ply: usize,
+        source: ParseSanError,
+    },
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseSquareError {
+    #[error("Provided string is not two characters long")]
+    IncorrectLength,
+    #[error("Unknown file, it has to be a letter from a to h")]
+    InvalidFile,
+    #[error("Unknown rank, it has to be a digit from 1 to 8")]
+    InvalidRank,
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("The coordinates are outside of the board")]
+pub struct OutOfBoardError;
+
+/// A reason that a position can't be reached in a game of chess
+#[derive(thiserror::Error, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PositionError {
+    #[error("{0:?} has no king")]
+    MissingKing(Color),
+    #[error("{0:?} has more than one king")]
+    TooManyKings(Color),
+    #[error("{0:?} has more than eight pawns")]
+    TooManyPawns(Color),
+    #[error("{0:?} has more than sixteen pieces")]
+    TooManyPieces(Color),
+    #[error("There is a pawn on the back rank at {0}")]
+    PawnOnBackRank(Square),
+    #[error("The side that just moved is in check")]
+    OpponentInCheck,
+    #[error("The side to move is checked by more than two pieces")]
+    TooManyCheckers,
+    #[error("{color:?} can castle without the king and rook on their starting squares")]
+    InvalidCastlingRights {
+        color: Color,
+        /// If it's the kingside castling right, otherwise it's the queenside one
+        kingside: bool,
+    },
+    #[error("The en passant pawn can't have just moved two squares")]
+    InvalidEnPassant,
+}
+
+/// An error from parsing a FEN string with `Game::from_fen_strict`
+#[derive(thiserror::Error, Debug)]
+pub enum FromFenStrictError {
+    #[error(transparent)]
+    InvalidFen(#[from] FromFenError),
+    #[error("The position is illegal: {}", .0.iter().map(|e| e.to_string()).collect::<Vec<_>>().join(", "))]
+    IllegalPosition(Vec<PositionError>),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseEpdError {
+    #[error("Invalid FEN on line {line}")]
+    InvalidFen { line: usize, source: FromFenError },
+    #[error("Invalid perft count \"{operation}\" on line {line}")]
+    InvalidPerftCount { line: usize, operation: String },
+}
+
+/// An error from packing a move that can't be played on a board
+#[derive(thiserror::Error, Debug)]
+pub enum PackMoveError {
+    #[error("Pawns can only promote to knights, bishops, rooks and queens")]
+    InvalidPromotion,
+    #[error(transparent)]
+    OutOfBoard(#[from] OutOfBoardError),
+}
@@ -0,0 +1,496 @@
+//! Static evaluation of positions
+//!
+//! The evaluation scores a position in centipawns from the perspective of the side to move, a
+//! positive score means that the side to move is better. Every term has a middlegame and an
+//! endgame value, and the two are blended by how much material is left on the board.
+//!
+//! All weights live in `EvalWeights`, so they can be tuned without touching the evaluation.
+
+use crate::{
+    chess::attacks::{king_attacks, piece_attacks},
+    Bitboard, Color, Game, MoveList, PieceType,
+};
+
+/// The phase of a position with all pieces on the board
+const MAX_PHASE: i32 = 24;
+
+/// The phase every piece type adds, indexed by `PieceType as usize`
+const PHASE: [i32; 6] = [0, 1, 1, 2, 4, 0];
+
+/// The squares of the a-file, shift it by x to get the other files
+const A_FILE: Bitboard = Bitboard(0x0101_0101_0101_0101);
+
+/// All piece types, in the order of `PieceType as usize`
+const PIECE_TYPES: [PieceType; 6] = [
+    PieceType::Pawn,
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+    PieceType::King,
+];
+
+/// The weights of every term of the evaluation
+///
+/// Weights that come in pairs are the middlegame and the endgame value. Tables indexed by piece
+/// type use `PieceType as usize`, and piece-square tables are written from white's side with a8
+/// first, the same order as the tiles of a `Board`. Black uses the tables mirrored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvalWeights {
+    /// The value of every piece type
+    pub material: [(i32, i32); 6],
+    /// The bonus of every piece type on every square
+    pub piece_squares: [[(i32, i32); 64]; 6],
+    /// The bonus for every legal move of a piece type
+    pub mobility: [(i32, i32); 6],
+    /// The bonus for every own pawn right in front of the king
+    pub pawn_shield: (i32, i32),
+    /// The penalty for every attack of an enemy piece on the squares around the king
+    pub king_attack: (i32, i32),
+    /// The penalty for every pawn behind another pawn of the same color on the same file
+    pub doubled_pawn: (i32, i32),
+    /// The penalty for every pawn without pawns of the same color on the files next to it
+    pub isolated_pawn: (i32, i32),
+    /// The bonus for a pawn that no enemy pawn can stop, indexed by how many ranks it has moved
+    /// from its starting rank, so the last entry is for a pawn on the seventh rank
+    pub passed_pawn: [(i32, i32); 6],
+}
+
+impl Default for EvalWeights {
+    fn default() -> Self {
+        let mut piece_squares = [[(0, 0); 64]; 6];
+        for (i, (mg, eg)) in PIECE_SQUARES.iter().enumerate() {
+            for square in 0..64 {
+                piece_squares[i][square] = (mg[square], eg[square]);
+            }
+        }
+
+        EvalWeights {
+            material: [
+                (82, 94),
+                (337, 281),
+                (365, 297),
+                (477, 512),
+                (1025, 936),
+                (0, 0),
+            ],
+            piece_squares,
+            mobility: [(0, 0), (4, 4), (3, 3), (2, 4), (1, 2), (0, 0)],
+            pawn_shield: (10, 0),
+            king_attack: (-8, -2),
+            doubled_pawn: (-10, -20),
+            isolated_pawn: (-10, -15),
+            passed_pawn: [(0, 0), (5, 10), (15, 25), (25, 45), (40, 75), (60, 110)],
+        }
+    }
+}
+
+/// Scores a game with the default weights
+///
+/// # Arguments
+/// * `game` - The game to score
+///
+/// # Returns
+/// * `i32` - The score in centipawns from the perspective of the side to move
+///
+/// # Examples
+/// ```
+/// use fritiofr_chess::{eval::evaluate, Game};
+///
+/// assert_eq!(evaluate(&Game::start_pos()), 0);
+///
+/// // White is a queen up, so it's good for white and bad for black
+/// let white_to_move = Game::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap();
+/// let black_to_move = Game::from_fen("4k3/8/8/8/8/8/8/3QK3 b - - 0 1").unwrap();
+/// assert!(evaluate(&white_to_move) > 800);
+/// assert!(evaluate(&black_to_move) < -800);
+/// ```
+pub fn evaluate(game: &Game) -> i32 {
+    evaluate_with(game, &EvalWeights::default())
+}
+
+/// Scores a game with custom weights
+///
+/// # Arguments
+/// * `game` - The game to score
+/// * `weights` - The weights of the evaluation terms
+///
+/// # Returns
+/// * `i32` - The score in centipawns from the perspective of the side to move
+pub fn evaluate_with(game: &Game, weights: &EvalWeights) -> i32 {
+    let board = game.get_board();
+
+    let phase = PIECE_TYPES
+        .iter()
+        .map(|&piece_type| {
+            board.type_pieces(piece_type).count() as i32 * PHASE[piece_type as usize]
+        })
+        .sum::<i32>()
+        .min(MAX_PHASE);
+
+    let (mg, eg) = [Color::White, Color::Black]
+        .into_iter()
+        .map(|color| {
+            let (mg, eg) = side_score(game, color, weights);
+            let sign = if color == Color::White { 1 } else { -1 };
+
+            (sign * mg, sign * eg)
+        })
+        .fold((0, 0), |(mg, eg), score| (mg + score.0, eg + score.1));
+
+    let score = (mg * phase + eg * (MAX_PHASE - phase)) / MAX_PHASE;
+
+    if game.get_turn() == Color::White {
+        score
+    } else {
+        -score
+    }
+}
+
+/// Returns the middlegame and endgame score of one color
+fn side_score(game: &Game, color: Color, weights: &EvalWeights) -> (i32, i32) {
+    let board = game.get_board();
+    let mut score = (0, 0);
+    let mut add = |(mg, eg): (i32, i32), times: i32| {
+        score.0 += mg * times;
+        score.1 += eg * times;
+    };
+
+    for piece_type in PIECE_TYPES {
+        for (x, y) in board.pieces(color, piece_type).squares() {
+            add(weights.material[piece_type as usize], 1);
+            add(
+                weights.piece_squares[piece_type as usize][table_index(color, x, y)],
+                1,
+            );
+        }
+    }
+
+    for (piece_type, moves) in mobility(game, color).into_iter().enumerate() {
+        add(weights.mobility[piece_type], moves);
+    }
+
+    let pawns = board.pieces(color, PieceType::Pawn);
+    let enemy_pawns = board.pieces(color.opposite(), PieceType::Pawn);
+
+    for (x, y) in pawns.squares() {
+        let file = Bitboard(A_FILE.0 << x);
+        let neighbours =
+            Bitboard((A_FILE.0 << x.saturating_sub(1)) | (A_FILE.0 << (x + 1).min(7))) & !file;
+        let ahead = ranks_ahead(color, y);
+
+        if !(pawns & file & ahead).is_empty() {
+            add(weights.doubled_pawn, 1);
+        }
+
+        if (pawns & neighbours).is_empty() {
+            add(weights.isolated_pawn, 1);
+        }
+
+        if (enemy_pawns & (file | neighbours) & ahead).is_empty() {
+            let advanced = match color {
+                Color::White => 6usize.saturating_sub(y),
+                Color::Black => y.saturating_sub(1),
+            };
+            add(weights.passed_pawn[advanced.min(5)], 1);
+        }
+    }
+
+    if let Some((king_x, king_y)) = board.get_king_pos(color) {
+        let zone = king_attacks((king_x, king_y)) | Bitboard::from_square((king_x, king_y));
+
+        let shield = zone & ranks_ahead(color, king_y) & pawns;
+        add(weights.pawn_shield, shield.count() as i32);
+
+        let occupancy = board.occupied();
+        let attacks = board
+            .color_pieces(color.opposite())
+            .squares()
+            .map(|(x, y)| {
+                let piece = board.get_tile(x, y).expect("The square is occupied");
+                (piece_attacks((x, y), piece, occupancy) & zone).count() as i32
+            })
+            .sum();
+        add(weights.king_attack, attacks);
+    }
+
+    score
+}
+
+/// Returns the number of legal moves of every piece type of a color, as if it was that color's
+/// turn
+fn mobility(game: &Game, color: Color) -> [i32; 6] {
+    let mut game = *game;
+    if game.get_turn() != color {
+        game.set_turn(color);
+    }
+
+    let mut moves = MoveList::new();
+    game.generate_into(&mut moves);
+
+    let board = game.get_board();
+    let mut mobility = [0; 6];
+    for mv in &moves {
+        let (x, y) = mv.from();
+        let piece = board.get_tile(x, y).expect("Moves start on a piece");
+        mobility[piece.piece_type as usize] += 1;
+    }
+
+    mobility
+}
+
+/// Returns all squares on the ranks in front of a rank, seen from a color
+fn ranks_ahead(color: Color, y: usize) -> Bitboard {
+    match color {
+        // White moves towards y = 0, those are the low bits
+        Color::White => Bitboard((1u64 << (y * 8)) - 1),
+        Color::Black => Bitboard(u64::MAX.checked_shl((y as u32 + 1) * 8).unwrap_or(0)),
+    }
+}
+
+/// Returns the index in a piece-square table, the tables are written for white so black's
+/// squares are mirrored
+fn table_index(color: Color, x: usize, y: usize) -> usize {
+    match color {
+        Color::White => y * 8 + x,
+        Color::Black => (7 - y) * 8 + x,
+    }
+}
+
+/// The default piece-square tables, the middlegame and the endgame table of every piece type
+#[rustfmt::skip]
+const PIECE_SQUARES: [([i32; 64], [i32; 64]); 6] = [
+    // Pawn
+    (
+        [
+              0,   0,   0,   0,   0,   0,   0,   0,
+             50,  50,  50,  50,  50,  50,  50,  50,
+             10,  10,  20,  30,  30,  20,  10,  10,
+              5,   5,  10,  25,  25,  10,   5,   5,
+              0,   0,   0,  20,  20,   0,   0,   0,
+              5,  -5, -10,   0,   0, -10,  -5,   5,
+              5,  10,  10, -20, -20,  10,  10,   5,
+              0,   0,   0,   0,   0,   0,   0,   0,
+        ],
+        [
+              0,   0,   0,   0,   0,   0,   0,   0,
+             80,  80,  80,  80,  80,  80,  80,  80,
+             50,  50,  50,  50,  50,  50,  50,  50,
+             30,  30,  30,  30,  30,  30,  30,  30,
+             15,  15,  15,  15,  15,  15,  15,  15,
+              5,   5,   5,   5,   5,   5,   5,   5,
+              0,   0,   0,   0,   0,   0,   0,   0,
+              0,   0,   0,   0,   0,   0,   0,   0,
+        ],
+    ),
+    // Knight
+    (
+        [
+            -50, -40, -30, -30, -30, -30, -40, -50,
+            -40, -20,   0,   0,   0,   0, -20, -40,
+            -30,   0,  10,  15,  15,  10,   0, -30,
+            -30,   5,  15,  20,  20,  15,   5, -30,
+            -30,   0,  15,  20,  20,  15,   0, -30,
+            -30,   5,  10,  15,  15,  10,   5, -30,
+            -40, -20,   0,   5,   5,   0, -20, -40,
+            -50, -40, -30, -30, -30, -30, -40, -50,
+        ],
+        [
+            -50, -40, -30, -30, -30, -30, -40, -50,
+            -40, -20,   0,   0,   0,   0, -20, -40,
+            -30,   0,  10,  15,  15,  10,   0, -30,
+            -30,   5,  15,  20,  20,  15,   5, -30,
+            -30,   0,  15,  20,  20,  15,   0, -30,
+            -30,   5,  10,  15,  15,  10,   5, -30,
+            -40, -20,   0,   5,   5,   0, -20, -40,
+            -50, -40, -30, -30, -30, -30, -40, -50,
+        ],
+    ),
+    // Bishop
+    (
+        [
+            -20, -10, -10, -10, -10, -10, -10, -20,
+            -10,   0,   0,   0,   0,   0,   0, -10,
+            -10,   0,   5,  10,  10,   5,   0, -10,
+            -10,   5,   5,  10,  10,   5,   5, -10,
+            -10,   0,  10,  10,  10,  10,   0, -10,
+            -10,  10,  10,  10,  10,  10,  10, -10,
+            -10,   5,   0,   0,   0,   0,   5, -10,
+            -20, -10, -10, -10, -10, -10, -10, -20,
+        ],
+        [
+            -20, -10, -10, -10, -10, -10, -10, -20,
+            -10,   0,   0,   0,   0,   0,   0, -10,
+            -10,   0,   5,  10,  10,   5,   0, -10,
+            -10,   5,   5,  10,  10,   5,   5, -10,
+            -10,   0,  10,  10,  10,  10,   0, -10,
+            -10,  10,  10,  10,  10,  10,  10, -10,
+            -10,   5,   0,   0,   0,   0,   5, -10,
+            -20, -10, -10, -10, -10, -10, -10, -20,
+        ],
+    ),
+    // Rook
+    (
+        [
+              0,   0,   0,   0,   0,   0,   0,   0,
+              5,  10,  10,  10,  10,  10,  10,   5,
+             -5,   0,   0,   0,   0,   0,   0,  -5,
+             -5,   0,   0,   0,   0,   0,   0,  -5,
+             -5,   0,   0,   0,   0,   0,   0,  -5,
+             -5,   0,   0,   0,   0,   0,   0,  -5,
+             -5,   0,   0,   0,   0,   0,   0,  -5,
+              0,   0,   0,   5,   5,   0,   0,   0,
+        ],
+        [
+              0,   0,   0,   0,   0,   0,   0,   0,
+             10,  10,  10,  10,  10,  10,  10,  10,
+              0,   0,   0,   0,   0,   0,   0,   0,
+              0,   0,   0,   0,   0,   0,   0,   0,
+              0,   0,   0,   0,   0,   0,   0,   0,
+              0,   0,   0,   0,   0,   0,   0,   0,
+              0,   0,   0,   0,   0,   0,   0,   0,
+              0,   0,   0,   0,   0,   0,   0,   0,
+        ],
+    ),
+    // Queen
+    (
+        [
+            -20, -10, -10,  -5,  -5, -10, -10, -20,
+            -10,   0,   0,   0,   0,   0,   0, -10,
+            -10,   0,   5,   5,   5,   5,   0, -10,
+             -5,   0,   5,   5,   5,   5,   0,  -5,
+              0,   0,   5,   5,   5,   5,   0,  -5,
+            -10,   5,   5,   5,   5,   5,   0, -10,
+            -10,   0,   5,   0,   0,   0,   0, -10,
+            -20, -10, -10,  -5,  -5, -10, -10, -20,
+        ],
+        [
+            -20, -10, -10,  -5,  -5, -10, -10, -20,
+            -10,   0,   0,   0,   0,   0,   0, -10,
+            -10,   0,   5,   5,   5,   5,   0, -10,
+             -5,   0,   5,  10,  10,   5,   0,  -5,
+             -5,   0,   5,  10,  10,   5,   0,  -5,
+            -10,   0,   5,   5,   5,   5,   0, -10,
+            -10,   0,   0,   0,   0,   0,   0, -10,
+            -20, -10, -10,  -5,  -5, -10, -10, -20,
+        ],
+    ),
+    // King, it hides in the middlegame and walks to the center in the endgame
+    (
+        [
+            -30, -40, -40, -50, -50, -40, -40, -30,
+            -30, -40, -40, -50, -50, -40, -40, -30,
+            -30, -40, -40, -50, -50, -40, -40, -30,
+            -30, -40, -40, -50, -50, -40, -40, -30,
+            -20, -30, -30, -40, -40, -30, -30, -20,
+            -10, -20, -20, -20, -20, -20, -20, -10,
+             20,  20,   0,   0,   0,   0,  20,  20,
+             20,  30,  10,   0,   0,  10,  30,  20,
+        ],
+        [
+            -50, -40, -30, -20, -20, -30, -40, -50,
+            -30, -20, -10,   0,   0, -10, -20, -30,
+            -30, -10,  20,  30,  30,  20, -10, -30,
+            -30, -10,  30,  40,  40,  30, -10, -30,
+            -30, -10,  30,  40,  40,  30, -10, -30,
+            -30, -10,  20,  30,  30,  20, -10, -30,
+            -30, -30,   0,   0,   0,   0, -30, -30,
+            -50, -30, -30, -30, -30, -30, -30, -50,
+        ],
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the FEN of a position with the colors swapped and the board mirrored
+    fn mirror(fen: &str) -> String {
+        let parts = fen.split(' ').collect::<Vec<_>>();
+
+        let board = parts[0]
+            .split('/')
+            .rev()
+            .map(|rank| rank.chars().map(swap_case).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("/");
+        let turn = if parts[1] == "w" { "b" } else { "w" };
+        let castling = parts[2].chars().map(swap_case).collect::<String>();
+
+        format!("{} {} {} - 0 1", board, turn, castling)
+    }
+
+    fn swap_case(c: char) -> char {
+        if c.is_ascii_uppercase() {
+            c.to_ascii_lowercase()
+        } else {
+            c.to_ascii_uppercase()
+        }
+    }
+
+    #[test]
+    pub fn mirrored_positions_should_have_the_same_score() {
+        let fens_to_test = vec![
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 0 1",
+            "4k3/8/8/8/8/8/8/3QK3 b - - 0 1",
+        ];
+
+        for fen in fens_to_test {
+            let game = Game::from_fen(fen).unwrap();
+            let mirrored = Game::from_fen(&mirror(fen)).unwrap();
+
+            assert_eq!(evaluate(&game), evaluate(&mirrored), "{}", fen);
+        }
+    }
+
+    #[test]
+    pub fn should_score_pawn_structure() {
+        // A passed pawn is better than a blocked one
+        let passed = Game::from_fen("4k3/8/8/3P4/8/8/8/4K3 w - - 0 1").unwrap();
+        let blocked = Game::from_fen("4k3/3p4/8/3P4/8/8/8/4K3 w - - 0 1").unwrap();
+        let material = EvalWeights::default().material[PieceType::Pawn as usize].1;
+        assert!(evaluate(&passed) > evaluate(&blocked) + material);
+
+        // Doubled and isolated pawns are worse than connected ones
+        let doubled = Game::from_fen("4k3/pp6/8/8/8/3P4/3P4/4K3 w - - 0 1").unwrap();
+        let connected = Game::from_fen("4k3/pp6/8/8/8/8/2PP4/4K3 w - - 0 1").unwrap();
+        assert!(evaluate(&connected) > evaluate(&doubled));
+    }
+
+    #[test]
+    pub fn should_give_pawns_on_the_seventh_rank_the_largest_passed_pawn_bonus() {
+        let weights = EvalWeights {
+            material: [(0, 0); 6],
+            piece_squares: [[(0, 0); 64]; 6],
+            mobility: [(0, 0); 6],
+            pawn_shield: (0, 0),
+            king_attack: (0, 0),
+            isolated_pawn: (0, 0),
+            passed_pawn: [(0, 0), (0, 0), (0, 0), (0, 0), (0, 0), (100, 100)],
+            ..EvalWeights::default()
+        };
+
+        let white = Game::from_fen("k7/3P4/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let black = Game::from_fen("4k3/8/8/8/8/8/3p4/K7 b - - 0 1").unwrap();
+        assert_eq!(evaluate_with(&white, &weights), 100);
+        assert_eq!(evaluate_with(&black, &weights), 100);
+
+        let sixth_rank = Game::from_fen("k7/8/3P4/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(evaluate_with(&sixth_rank, &weights), 0);
+    }
+
+    #[test]
+    pub fn weights_should_be_tunable() {
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/3QK3 w - - 0 1").unwrap();
+
+        let mut weights = EvalWeights::default();
+        weights.material[PieceType::Queen as usize] = (0, 0);
+        weights.piece_squares = [[(0, 0); 64]; 6];
+        weights.mobility = [(0, 0); 6];
+        weights.king_attack = (0, 0);
+
+        assert_eq!(evaluate_with(&game, &weights), 0);
+    }
+}
@@ -1,43 +1,107 @@
-use crate::{error::GameApplyMoveError, Color, Game, Move, Piece, PieceType};
+use crate::{error::GameApplyMoveError, Color, Game, Move, MoveList, Piece, PieceType, Square};
 
 impl Game {
     /// Applies a move to the game
     ///
+    /// The move has to be legal for the current position, if it isn't the game is left unchanged
+    /// and the error says why the move was rejected.
+    ///
     /// # Arguments
     /// * `mv` - The move to apply
     ///
     /// # Returns
-    /// * `Result<(), Box<dyn Error>>` - A result that holds nothing if the move was applied
-    /// successfully or an error if the move was invalid
+    /// * `Result<(), GameApplyMoveError>` - A result that holds nothing if the move was applied
+    ///   successfully or an error if the move was invalid
     ///
     /// # Examples
     /// ```
-    /// use fritiofr_chess::{Game, Move};
+    /// use fritiofr_chess::{error::GameApplyMoveError, Game, Move};
     ///
     /// let mut game = Game::start_pos();
     /// // Move the pawn on e2 to e3
-    /// game.apply_move(Move::Quiet { from: (4, 6), to: (4, 5) });
+    /// game.apply_move(Move::Quiet { from: (4, 6), to: (4, 5) }).unwrap();
+    ///
+    /// // White can't move twice in a row
+    /// assert!(matches!(
+    ///     game.apply_move(Move::Quiet { from: (3, 6), to: (3, 5) }),
+    ///     Err(GameApplyMoveError::NotYourTurn)
+    /// ));
     /// ```
     pub fn apply_move(&mut self, mv: Move) -> Result<(), GameApplyMoveError> {
-        self.en_passant = None;
+        self.check_move(mv)?;
+        self.apply_move_unchecked(mv);
 
-        if mv.is_capture() {
-            let (c_x, c_y) = mv.capture().expect("This is a capture move");
+        Ok(())
+    }
 
-            self.board.remove_tile(c_x, c_y);
-            remove_castling_rights_pos(self, (c_x, c_y));
+    /// Returns why a move can't be applied to the game, or nothing if the move is legal
+    pub(crate) fn check_move(&self, mv: Move) -> Result<(), GameApplyMoveError> {
+        let (from_x, from_y) = mv.from();
+        let from = Square::try_from(mv.from()).map_err(|_| GameApplyMoveError::IllegalMove)?;
+
+        let piece = self
+            .board
+            .piece_at(from)
+            .ok_or(GameApplyMoveError::NoPieceOnSquare(from))?;
+
+        if piece.color != self.turn {
+            return Err(GameApplyMoveError::NotYourTurn);
+        }
+
+        let mut moves = MoveList::new();
+        self.gen_pseudo_legal_moves(from_x, from_y, false, &mut moves);
+
+        if !moves.contains(&mv) {
+            return Err(GameApplyMoveError::IllegalMove);
+        }
+
+        if !self.is_legal(mv, &self.legality()) {
+            return Err(GameApplyMoveError::LeavesKingInCheck);
         }
 
+        Ok(())
+    }
+
+    /// Applies a move to the game without checking that it's legal
+    ///
+    /// This is for hot paths where the move is known to be legal, like moves that were just
+    /// generated for the same position. Applying an illegal move leaves the game in a state that
+    /// can't happen in chess.
+    ///
+    /// # Arguments
+    /// * `mv` - The move to apply, it has to be a legal move for the current position
+    ///
+    /// # Panics
+    /// If there is no piece on the from square of the move
+    pub fn apply_move_unchecked(&mut self, mv: Move) {
         let (to_x, to_y) = mv.to();
         let (from_x, from_y) = mv.from();
 
         let piece = self
             .board
             .get_tile(from_x, from_y)
-            .ok_or(GameApplyMoveError::InvalidMove)?;
+            .expect("There is a piece on the from square of the move");
+
+        // The castling and en passant keys are xor'ed out here and back in when the move is done
+        self.hash ^= self.castling_key() ^ self.en_passant_key();
+
+        self.en_passant = None;
+
+        if mv.is_capture() {
+            let (c_x, c_y) = mv.capture().expect("This is a capture move");
+
+            self.remove_tile(c_x, c_y);
+            remove_castling_rights_pos(self, (c_x, c_y));
+        }
+
+        self.remove_tile(from_x, from_y);
+        self.set_tile(to_x, to_y, piece);
 
-        self.board.remove_tile(from_x, from_y);
-        self.board.set_tile(to_x, to_y, piece);
+        if mv.is_capture() || piece.piece_type == PieceType::Pawn {
+            self.halfmove_clock = 0;
+        } else {
+            self.halfmove_clock = self.halfmove_clock.saturating_add(1);
+        }
 
         // Remove castling rights if the type is king
         if piece.piece_type == PieceType::King {
@@ -57,12 +121,8 @@ impl Game {
                 rook_from, rook_to, ..
             } => {
                 if let Some(rook_piece) = self.board.get_tile(rook_from.0, rook_from.1) {
-                    remove_castling_rights_color(self, rook_piece.color);
-
-                    self.board.remove_tile(rook_from.0, rook_from.1);
-                    self.board.set_tile(rook_to.0, rook_to.1, rook_piece);
-                } else {
-                    return Err(GameApplyMoveError::InvalidMove);
+                    self.remove_tile(rook_from.0, rook_from.1);
+                    self.set_tile(rook_to.0, rook_to.1, rook_piece);
                 }
             }
             Move::QuietPromotion { .. } | Move::CapturePromotion { .. } => {
@@ -71,7 +131,7 @@ impl Game {
                     .get_tile(to_x, to_y)
                     .expect("The to tile is set further up in this function");
 
-                self.board.set_tile(
+                self.set_tile(
                     to_x,
                     to_y,
                     Piece {
@@ -83,9 +143,13 @@ impl Game {
             _ => (),
         };
 
-        self.turn = self.turn.opposite();
+        if self.turn == Color::Black {
+            self.fullmove_number = self.fullmove_number.saturating_add(1);
+        }
 
-        Ok(())
+        self.flip_turn();
+
+        self.hash ^= self.castling_key() ^ self.en_passant_key();
     }
 }
 
@@ -113,3 +177,101 @@ fn remove_castling_rights_color(game: &mut Game, color: Color) {
         game.black_queenside_castle = false;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn should_reject_illegal_moves_without_changing_the_game() {
+        let moves_to_test = vec![
+            // A rook jumping across the board
+            (
+                "4k3/8/8/8/8/8/P7/R3K3 w Q - 0 1",
+                Move::Quiet {
+                    from: (0, 7),
+                    to: (0, 0),
+                },
+                GameApplyMoveError::IllegalMove,
+            ),
+            // A move by the wrong color
+            (
+                "4k3/p7/8/8/8/8/8/4K3 w - - 0 1",
+                Move::Quiet {
+                    from: (0, 1),
+                    to: (0, 2),
+                },
+                GameApplyMoveError::NotYourTurn,
+            ),
+            // A castle without castling rights
+            (
+                "4k3/8/8/8/8/8/8/4K2R w - - 0 1",
+                Move::Castle {
+                    from: (4, 7),
+                    to: (6, 7),
+                    rook_from: (7, 7),
+                    rook_to: (5, 7),
+                },
+                GameApplyMoveError::IllegalMove,
+            ),
+            // A castle with castling rights but without the rook
+            (
+                "4k3/8/8/8/8/8/8/4K3 w K - 0 1",
+                Move::Castle {
+                    from: (4, 7),
+                    to: (6, 7),
+                    rook_from: (7, 7),
+                    rook_to: (5, 7),
+                },
+                GameApplyMoveError::IllegalMove,
+            ),
+            // A castle by a king that isn't on its starting square
+            (
+                "4k3/8/8/8/8/8/8/3K3R w K - 0 1",
+                Move::Castle {
+                    from: (4, 7),
+                    to: (6, 7),
+                    rook_from: (7, 7),
+                    rook_to: (5, 7),
+                },
+                GameApplyMoveError::NoPieceOnSquare(Square::try_from((4, 7)).unwrap()),
+            ),
+            // A pinned knight
+            (
+                "4k3/4r3/8/8/8/8/4N3/4K3 w - - 0 1",
+                Move::Quiet {
+                    from: (4, 6),
+                    to: (2, 5),
+                },
+                GameApplyMoveError::LeavesKingInCheck,
+            ),
+            // A capture of a pawn that can't be captured en passant
+            (
+                "4k3/8/8/3pP3/8/8/8/4K3 w - - 0 1",
+                Move::Capture {
+                    from: (4, 3),
+                    to: (3, 2),
+                    capture: (3, 3),
+                },
+                GameApplyMoveError::IllegalMove,
+            ),
+        ];
+
+        for (fen, mv, error) in moves_to_test {
+            let mut game = Game::from_fen(fen).unwrap();
+
+            assert_eq!(game.apply_move(mv), Err(error), "{}", fen);
+            assert_eq!(game, Game::from_fen(fen).unwrap());
+        }
+
+        let mut game = Game::start_pos();
+        assert_eq!(
+            game.apply_move(Move::Quiet {
+                from: (4, 4),
+                to: (4, 3)
+            }),
+            Err(GameApplyMoveError::NoPieceOnSquare("e4".parse().unwrap()))
+        );
+        assert_eq!(game, Game::start_pos());
+    }
+}
@@ -1,80 +1,63 @@
-use std::collections::HashSet;
-
-use crate::{error::FromFenError, Board, Color, Game, PieceType};
+use crate::{
+    chess::notation::{parse_square, square_name},
+    error::{FenErrorKind, FenField, FromFenError},
+    Board, Color, Game, PieceType,
+};
 
 impl Game {
     /// Creates a new game from a FEN string
     ///
+    /// The FEN string has to have all six fields, use `from_fen_lenient` to also accept FEN
+    /// strings without the halfmove clock and fullmove number.
+    ///
+    /// Any string can be passed in, invalid FEN strings never panic. The error says which field is
+    /// wrong and the byte offset of the problem.
+    ///
     /// # Arguments
     /// * `fen` - A string that holds the FEN string
     ///
     /// # Returns
     /// * `Result<game, FromFenError>` - A result that holds the game if the fen string is valid
-    /// or an error if the FEN string is invalid
+    ///   or an error if the FEN string is invalid
     ///
     /// # Examples
     /// ```
     /// use fritiofr_chess::Game;
     ///
     /// // Starting position
-    /// let game = Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -");
+    /// let game = Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1");
+    ///
+    /// let error = Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e9 0 1");
+    /// assert_eq!(
+    ///     error.unwrap_err().to_string(),
+    ///     "Unknown character in the en passant field at byte 51"
+    /// );
     /// ```
     pub fn from_fen(fen: &str) -> Result<Game, FromFenError> {
-        let fen_parts = fen.split(' ').collect::<Vec<&str>>();
-
-        let fen_part_pieces = fen_parts[0];
-        let fen_part_turn = fen_parts[1];
-        let fen_part_castling = fen_parts[2];
-        let fen_part_en_passant = fen_parts[3];
-
-        if fen_parts.len() != 4 {
-            return Err(FromFenError::IncorrectAmountOfParts);
-        }
-
-        let board = Board::from_fen(fen_part_pieces)?;
-
-        let turn = match fen_part_turn {
-            "w" => Color::White,
-            "b" => Color::Black,
-            _ => return Err(FromFenError::UnknownTurn),
-        };
-
-        let castling = castling_part(fen_part_castling)?;
-
-        let en_passant = en_passant(fen_part_en_passant)?;
-
-        let en_passant = if let Some((ep_x, ep_y)) = en_passant {
-            // Because i store en passant as the tile of the pawn that can be captured,
-            let ep_y = if turn == Color::White {
-                ep_y + 1
-            } else {
-                ep_y - 1
-            };
-
-            let ocp_piece = board.get_tile(ep_x, ep_y);
-
-            if let Some(piece) = ocp_piece {
-                if piece.piece_type != PieceType::Pawn || piece.color == turn {
-                    return Err(FromFenError::InvalidEnPassant);
-                }
-            } else {
-                return Err(FromFenError::InvalidEnPassant);
-            }
-
-            Some((ep_x, ep_y))
-        } else {
-            None
-        };
+        parse_fen(fen, false)
+    }
 
-        Ok(Game {
-            board,
-            turn,
-            en_passant,
-            white_kingside_castle: castling[0],
-            white_queenside_castle: castling[1],
-            black_kingside_castle: castling[2],
-            black_queenside_castle: castling[3],
-        })
+    /// Creates a new game from a FEN string that might be missing the last two fields
+    ///
+    /// This accepts both the full six field FEN and the four field form that EPD uses. If the
+    /// halfmove clock and fullmove number are missing they are set to 0 and 1.
+    ///
+    /// # Arguments
+    /// * `fen` - A string that holds the FEN string
+    ///
+    /// # Returns
+    /// * `Result<game, FromFenError>` - A result that holds the game if the fen string is valid
+    ///   or an error if the FEN string is invalid
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::Game;
+    ///
+    /// let game = Game::from_fen_lenient("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -");
+    /// assert_eq!(game.unwrap(), Game::start_pos());
+    /// ```
+    pub fn from_fen_lenient(fen: &str) -> Result<Game, FromFenError> {
+        parse_fen(fen, true)
     }
 
     /// Returns the game as a FEN string
@@ -119,84 +102,190 @@ impl Game {
                 ep_y + 1
             };
 
-            let rank = '1' as usize + (7 - ep_y);
-            let file = 'a' as usize + ep_x;
-
-            format!("{}{}", char::from(file as u8), char::from(rank as u8))
+            square_name((ep_x, ep_y))
         } else {
             "-".to_string()
         };
 
-        format!("{} {} {} {}", board, turn, castling, en_passant)
+        format!(
+            "{} {} {} {} {} {}",
+            board, turn, castling, en_passant, self.halfmove_clock, self.fullmove_number
+        )
     }
 }
 
-fn castling_part(fen_part: &str) -> Result<[bool; 4], FromFenError> {
-    if fen_part == "-" {
-        return Ok([false; 4]);
+/// The fields of a FEN string, in order
+const FIELDS: [FenField; 6] = [
+    FenField::Pieces,
+    FenField::Turn,
+    FenField::Castling,
+    FenField::EnPassant,
+    FenField::HalfmoveClock,
+    FenField::FullmoveNumber,
+];
+
+/// Internal helper that parses a FEN string, if `lenient` is set the halfmove clock and fullmove
+/// number are optional
+///
+/// No input makes this panic, every problem is returned as an error with the field and byte
+/// offset where it was found.
+fn parse_fen(fen: &str, lenient: bool) -> Result<Game, FromFenError> {
+    // Every part together with its byte offset in the FEN string
+    let mut fen_parts = vec![];
+    let mut offset = 0;
+    for part in fen.split(' ') {
+        fen_parts.push((offset, part));
+        offset += part.len() + 1;
     }
 
-    let mut castling: [bool; 4] = [false; 4];
-    let chars = fen_part.chars().collect::<Vec<char>>();
+    if fen_parts.len() > 6 {
+        return Err(FromFenError {
+            kind: FenErrorKind::IncorrectAmountOfParts,
+            field: FenField::FullmoveNumber,
+            offset: fen_parts[6].0,
+        });
+    }
+    if fen_parts.len() != 6 && !(lenient && fen_parts.len() == 4) {
+        return Err(FromFenError {
+            kind: FenErrorKind::IncorrectAmountOfParts,
+            field: FIELDS[fen_parts.len()],
+            offset: fen.len(),
+        });
+    }
+
+    let error = |kind, field: FenField, offset| FromFenError {
+        kind,
+        field,
+        offset: fen_parts[field as usize].0 + offset,
+    };
+
+    let halfmove_clock = match fen_parts.get(4) {
+        Some((_, part)) => parse_number(part).ok_or(error(
+            FenErrorKind::InvalidHalfmoveClock,
+            FenField::HalfmoveClock,
+            0,
+        ))?,
+        None => 0,
+    };
+
+    let fullmove_number = match fen_parts.get(5) {
+        Some((_, part)) => parse_number(part).filter(|n| *n > 0).ok_or(error(
+            FenErrorKind::InvalidFullmoveNumber,
+            FenField::FullmoveNumber,
+            0,
+        ))?,
+        None => 1,
+    };
+
+    let board = Board::from_fen(fen_parts[0].1)?;
+
+    let turn = match fen_parts[1].1 {
+        "w" => Color::White,
+        "b" => Color::Black,
+        _ => return Err(error(FenErrorKind::UnknownTurn, FenField::Turn, 0)),
+    };
+
+    let castling = castling_part(fen_parts[2].1)
+        .map_err(|(kind, offset)| error(kind, FenField::Castling, offset))?;
+
+    let en_passant =
+        en_passant(fen_parts[3].1).map_err(|kind| error(kind, FenField::EnPassant, 0))?;
+
+    let en_passant = if let Some((ep_x, ep_y)) = en_passant {
+        let invalid = error(FenErrorKind::InvalidEnPassant, FenField::EnPassant, 0);
+
+        // Because i store en passant as the tile of the pawn that can be captured, the square
+        // behind the pawn has to be on the sixth rank for white and the third rank for black
+        let ep_y = match (turn, ep_y) {
+            (Color::White, 2) => 3,
+            (Color::Black, 5) => 4,
+            _ => return Err(invalid),
+        };
+
+        let ocp_piece = board.get_tile(ep_x, ep_y);
+
+        if let Some(piece) = ocp_piece {
+            if piece.piece_type != PieceType::Pawn || piece.color == turn {
+                return Err(invalid);
+            }
+        } else {
+            return Err(invalid);
+        }
+
+        Some((ep_x, ep_y))
+    } else {
+        None
+    };
 
-    if chars.len() > 4 {
-        return Err(FromFenError::IncorrectLength);
+    let mut game = Game {
+        board,
+        turn,
+        en_passant,
+        white_kingside_castle: castling[0],
+        white_queenside_castle: castling[1],
+        black_kingside_castle: castling[2],
+        black_queenside_castle: castling[3],
+        halfmove_clock,
+        fullmove_number,
+        hash: 0,
+    };
+    game.hash = game.compute_hash();
+
+    Ok(game)
+}
+
+/// Parses a number that only consists of digits
+fn parse_number(part: &str) -> Option<u32> {
+    if part.is_empty() || !part.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
     }
 
-    if chars.len() != chars.iter().collect::<HashSet<&char>>().len() {
-        return Err(FromFenError::RepeatingCharactersInCastlingPart);
+    part.parse().ok()
+}
+
+/// Parses the castling part, errors hold the byte offset in the part
+fn castling_part(fen_part: &str) -> Result<[bool; 4], (FenErrorKind, usize)> {
+    if fen_part == "-" {
+        return Ok([false; 4]);
+    }
+
+    if fen_part.is_empty() || fen_part.len() > 4 {
+        return Err((FenErrorKind::IncorrectLength, 0));
     }
 
-    for c in chars {
-        match c {
-            'K' => castling[0] = true,
-            'Q' => castling[1] = true,
-            'k' => castling[2] = true,
-            'q' => castling[3] = true,
-            _ => return Err(FromFenError::UnknownCharacter),
+    let mut castling: [bool; 4] = [false; 4];
+
+    for (offset, c) in fen_part.char_indices() {
+        let right = match c {
+            'K' => 0,
+            'Q' => 1,
+            'k' => 2,
+            'q' => 3,
+            _ => return Err((FenErrorKind::UnknownCharacter, offset)),
+        };
+
+        if castling[right] {
+            return Err((FenErrorKind::RepeatingCharactersInCastlingPart, offset));
         }
+
+        castling[right] = true;
     }
 
     Ok(castling)
 }
 
-fn en_passant(fen_part: &str) -> Result<Option<(usize, usize)>, FromFenError> {
+fn en_passant(fen_part: &str) -> Result<Option<(usize, usize)>, FenErrorKind> {
     if fen_part == "-" {
         return Ok(None);
     }
 
-    let chars = fen_part.chars().collect::<Vec<char>>();
-
-    if chars.len() != 2 {
-        return Err(FromFenError::IncorrectAmountOfTiles);
+    if fen_part.chars().count() != 2 {
+        return Err(FenErrorKind::IncorrectLength);
     }
 
-    let file = match chars[0] {
-        'a' => 0,
-        'b' => 1,
-        'c' => 2,
-        'd' => 3,
-        'e' => 4,
-        'f' => 5,
-        'g' => 6,
-        'h' => 7,
-        _ => return Err(FromFenError::UnknownCharacter),
-    };
-
-    // Yes, this is super odd, but i accidentally made the board uppside down, oops...
-    let rank = match chars[1] {
-        '1' => 7,
-        '2' => 6,
-        '3' => 5,
-        '4' => 4,
-        '5' => 3,
-        '6' => 2,
-        '7' => 1,
-        '8' => 0,
-        _ => return Err(FromFenError::UnknownCharacter),
-    };
-
-    Ok(Some((file, rank)))
+    parse_square(fen_part)
+        .map(Some)
+        .ok_or(FenErrorKind::UnknownCharacter)
 }
 
 #[cfg(test)]
@@ -206,10 +295,10 @@ mod tests {
     #[test]
     pub fn fen_should_be_same_as_from_fen() {
         let fens_to_test = vec![
-            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -",
-            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b - -",
-            "5bnr/pp1p1ppp/nbrp4/1k2pQN1/2B1q3/6N1/PPPRPPPP/R1B1K3 w Q e6",
-            "rnbqkbnr/pppppppp/8/8/2P5/8/PP1PPPPP/RNBQKBNR b KQkq c3",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b - - 0 1",
+            "5bnr/pp1p1ppp/nbrp4/1k2pQN1/2B1q3/6N1/PPPRPPPP/R1B1K3 w Q e6 0 1",
+            "rnbqkbnr/pppppppp/8/8/2P5/8/PP1PPPPP/RNBQKBNR b KQkq c3 0 1",
         ];
 
         for fen in fens_to_test {
@@ -217,4 +306,173 @@ mod tests {
             assert_eq!(board.fen(), fen);
         }
     }
+
+    #[test]
+    pub fn from_fen_should_require_six_parts() {
+        assert!(matches!(
+            Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -"),
+            Err(FromFenError {
+                kind: FenErrorKind::IncorrectAmountOfParts,
+                ..
+            })
+        ));
+        assert!(matches!(
+            Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - x 1"),
+            Err(FromFenError {
+                kind: FenErrorKind::InvalidHalfmoveClock,
+                ..
+            })
+        ));
+        assert!(matches!(
+            Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 0"),
+            Err(FromFenError {
+                kind: FenErrorKind::InvalidFullmoveNumber,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    pub fn from_fen_errors_should_point_at_the_problem() {
+        let errors_to_test = vec![
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR",
+                FenField::Turn,
+                43,
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1 x",
+                FenField::FullmoveNumber,
+                57,
+            ),
+            (
+                "rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+                FenField::Pieces,
+                18,
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1",
+                FenField::Pieces,
+                42,
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1",
+                FenField::Turn,
+                44,
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkK - 0 1",
+                FenField::Castling,
+                49,
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e9 0 1",
+                FenField::EnPassant,
+                51,
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq a8 0 1",
+                FenField::EnPassant,
+                51,
+            ),
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - -1 1",
+                FenField::HalfmoveClock,
+                53,
+            ),
+        ];
+
+        for (fen, field, offset) in errors_to_test {
+            let error = Game::from_fen(fen).unwrap_err();
+            assert_eq!((error.field, error.offset), (field, offset), "{}", fen);
+        }
+    }
+
+    #[test]
+    pub fn from_fen_should_never_panic() {
+        let fens_to_test = vec![
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b kq - 10 20",
+        ];
+        let replacements = ['/', ' ', '-', '0', '8', '9', 'K', 'x', 'a', 'h', '\u{e9}'];
+
+        for fen in fens_to_test {
+            for i in 0..=fen.len() {
+                let _ = Game::from_fen(&fen[..i]);
+                let _ = Game::from_fen_lenient(&fen[..i]);
+                let _ = Board::from_fen(&fen[..i]);
+
+                for c in replacements {
+                    let mut mutated = fen.to_string();
+                    if i < fen.len() {
+                        mutated.replace_range(i..i + 1, &c.to_string());
+                    } else {
+                        mutated.push(c);
+                    }
+
+                    if let Ok(game) = Game::from_fen(&mutated) {
+                        assert_eq!(Game::from_fen(&game.fen()).unwrap(), game);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn from_fen_lenient_should_accept_four_parts() {
+        let game =
+            Game::from_fen_lenient("r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b - -")
+                .unwrap();
+        assert_eq!(
+            game.fen(),
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b - - 0 1"
+        );
+
+        let game = Game::from_fen_lenient("4k3/8/8/8/8/8/8/4K3 w - - 12 34").unwrap();
+        assert_eq!(game.fen(), "4k3/8/8/8/8/8/8/4K3 w - - 12 34");
+    }
+
+    #[test]
+    pub fn apply_move_should_update_clocks() {
+        let mut game = Game::start_pos();
+
+        for (san, fen) in [
+            (
+                "Nf3",
+                "rnbqkbnr/pppppppp/8/8/8/5N2/PPPPPPPP/RNBQKB1R b KQkq - 1 1",
+            ),
+            (
+                "Nc6",
+                "r1bqkbnr/pppppppp/2n5/8/8/5N2/PPPPPPPP/RNBQKB1R w KQkq - 2 2",
+            ),
+            (
+                "e4",
+                "r1bqkbnr/pppppppp/2n5/8/4P3/5N2/PPPP1PPP/RNBQKB1R b KQkq e3 0 2",
+            ),
+            (
+                "Nb4",
+                "r1bqkbnr/pppppppp/8/8/1n2P3/5N2/PPPP1PPP/RNBQKB1R w KQkq - 1 3",
+            ),
+            (
+                "Bc4",
+                "r1bqkbnr/pppppppp/8/8/1nB1P3/5N2/PPPP1PPP/RNBQK2R b KQkq - 2 3",
+            ),
+            (
+                "Nxc2+",
+                "r1bqkbnr/pppppppp/8/8/2B1P3/5N2/PPnP1PPP/RNBQK2R w KQkq - 0 4",
+            ),
+        ] {
+            game.apply_move(game.parse_san(san).unwrap()).unwrap();
+            assert_eq!(game.fen(), fen);
+        }
+
+        // The clocks stop at their max instead of overflowing
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/4K2R w - - 4294967295 1").unwrap();
+        game.apply_move(game.parse_san("Rh2").unwrap()).unwrap();
+        assert_eq!(game.get_halfmove_clock(), u32::MAX);
+
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/8/4K2R b - - 0 4294967295").unwrap();
+        game.apply_move(game.parse_san("Kd7").unwrap()).unwrap();
+        assert_eq!(game.get_fullmove_number(), u32::MAX);
+    }
 }
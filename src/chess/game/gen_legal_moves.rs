@@ -0,0 +1,227 @@
+use crate::{
+    chess::attacks::{
+        between, bishop_attacks, knight_attacks, line, pawn_attacks, piece_attacks, rook_attacks,
+    },
+    Bitboard, Game, Move, MoveList, PieceType,
+};
+
+/// Everything needed to tell if a pseudo legal move is legal, calculated once per position
+pub(crate) struct Legality {
+    /// The king of the current turn, if there is one
+    king: Option<(usize, usize)>,
+    /// The pieces that give check
+    checkers: Bitboard,
+    /// The squares a move other than a king move has to end on to get out of check
+    check_mask: Bitboard,
+    /// The pieces of the current turn that can't leave the line to their king
+    pinned: Bitboard,
+    /// The squares the king can't move to, the opponent's attacks as if the king wasn't there
+    king_danger: Bitboard,
+}
+
+impl Game {
+    /// Calculates the checks, pins and attacked squares of the position
+    pub(crate) fn legality(&self) -> Legality {
+        let Some(king) = self.board.get_king_pos(self.turn) else {
+            // Without a king every pseudo legal move is legal
+            return Legality {
+                king: None,
+                checkers: Bitboard::EMPTY,
+                check_mask: Bitboard::FULL,
+                pinned: Bitboard::EMPTY,
+                king_danger: Bitboard::EMPTY,
+            };
+        };
+
+        let enemy = self.turn.opposite();
+        let us = self.board.color_pieces(self.turn);
+        let them = self.board.color_pieces(enemy);
+        let occupancy = self.board.occupied();
+
+        let checkers = self.board.attackers(king, enemy);
+        let check_mask = match checkers.count() {
+            0 => Bitboard::FULL,
+            1 => {
+                let checker = checkers.first().expect("There is one checker");
+                checkers | between(king, checker)
+            }
+            _ => Bitboard::EMPTY,
+        };
+
+        // Sliders that would attack the king if only the enemy pieces were on the board, with
+        // exactly one of our pieces in the way that piece is pinned
+        let rooks =
+            self.board.type_pieces(PieceType::Rook) | self.board.type_pieces(PieceType::Queen);
+        let bishops =
+            self.board.type_pieces(PieceType::Bishop) | self.board.type_pieces(PieceType::Queen);
+        let snipers =
+            ((rook_attacks(king, them) & rooks) | (bishop_attacks(king, them) & bishops)) & them;
+
+        let mut pinned = Bitboard::EMPTY;
+        for sniper in snipers.squares() {
+            let blockers = between(king, sniper) & occupancy;
+
+            if blockers.count() == 1 && !(blockers & us).is_empty() {
+                pinned |= blockers;
+            }
+        }
+
+        // The king is removed so it can't hide behind itself from a slider
+        let without_king = occupancy & !Bitboard::from_square(king);
+        let king_danger = them.squares().fold(Bitboard::EMPTY, |danger, (x, y)| {
+            let piece = self.board.get_tile(x, y).expect("The square is occupied");
+            danger | piece_attacks((x, y), piece, without_king)
+        });
+
+        Legality {
+            king: Some(king),
+            checkers,
+            check_mask,
+            pinned,
+            king_danger,
+        }
+    }
+
+    /// Returns if a pseudo legal move of the current turn is legal
+    ///
+    /// # Arguments
+    /// * `mv` - A pseudo legal move of the current turn
+    /// * `legality` - The legality of the current position
+    pub(crate) fn is_legal(&self, mv: Move, legality: &Legality) -> bool {
+        let Some(king) = legality.king else {
+            return true;
+        };
+
+        let from = mv.from();
+        let to = mv.to();
+
+        if from == king {
+            // Castling is only generated when the king isn't in check and doesn't pass through
+            // an attacked square
+            return mv.is_castle() || !legality.king_danger.contains(to);
+        }
+
+        let capture = mv.capture();
+        if capture.is_some_and(|capture| capture != to) {
+            return self.is_legal_en_passant(from, to, capture.expect("This is a capture"), king);
+        }
+
+        legality.check_mask.contains(to)
+            && (!legality.pinned.contains(from) || line(king, from).contains(to))
+    }
+
+    /// Returns if an en passant capture leaves the king safe
+    ///
+    /// Both pawns leave the rank at once, which can uncover a slider along the rank, so the
+    /// attacks on the king are calculated for the board after the capture.
+    fn is_legal_en_passant(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+        capture: (usize, usize),
+        king: (usize, usize),
+    ) -> bool {
+        let occupancy = (self.board.occupied()
+            & !Bitboard::from_square(from)
+            & !Bitboard::from_square(capture))
+            | Bitboard::from_square(to);
+        let them = self.board.color_pieces(self.turn.opposite()) & !Bitboard::from_square(capture);
+
+        let queens = self.board.type_pieces(PieceType::Queen);
+        let rooks = self.board.type_pieces(PieceType::Rook) | queens;
+        let bishops = self.board.type_pieces(PieceType::Bishop) | queens;
+
+        let attackers = (rook_attacks(king, occupancy) & rooks)
+            | (bishop_attacks(king, occupancy) & bishops)
+            | (knight_attacks(king) & self.board.type_pieces(PieceType::Knight))
+            | (pawn_attacks(king, self.turn) & self.board.type_pieces(PieceType::Pawn));
+
+        (attackers & them).is_empty()
+    }
+
+    /// Generates all legal moves for a piece of the current turn and adds them to the end of
+    /// `moves`
+    ///
+    /// # Arguments
+    /// * `x` - The x coordinate of the piece
+    /// * `y` - The y coordinate of the piece
+    /// * `legality` - The legality of the current position
+    /// * `moves` - The list to add the moves to
+    pub(crate) fn gen_legal_moves(
+        &self,
+        x: usize,
+        y: usize,
+        legality: &Legality,
+        moves: &mut MoveList,
+    ) {
+        // In double check only the king can move
+        let is_king = legality.king == Some((x, y));
+        if legality.checkers.count() > 1 && !is_king {
+            return;
+        }
+
+        let start = moves.len();
+        self.gen_pseudo_legal_moves(x, y, !legality.checkers.is_empty(), moves);
+        moves.retain_from(start, |mv| self.is_legal(*mv, legality));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn should_respect_pins_and_checks() {
+        // The pawn on b5 can't take en passant, both pawns would leave the rank of the king
+        let game = Game::from_fen("8/8/8/KPp4r/8/8/8/7k w - c6 0 1").unwrap();
+        assert!(game
+            .gen_moves(1, 3)
+            .unwrap()
+            .iter()
+            .all(|mv| mv.capture().is_none()));
+
+        // The bishop on d2 is pinned by the bishop on a5 and can only move along the pin
+        let game = Game::from_fen("4k3/8/8/b7/8/8/3B4/4K3 w - - 0 1").unwrap();
+        let mut targets = game
+            .gen_moves(3, 6)
+            .unwrap()
+            .iter()
+            .map(|mv| mv.to())
+            .collect::<Vec<_>>();
+        targets.sort();
+        assert_eq!(targets, vec![(0, 3), (1, 4), (2, 5)]);
+
+        // In double check only the king can move
+        let game = Game::from_fen("4k3/8/8/8/8/5n2/8/R3K1r1 w Q - 0 1").unwrap();
+        assert!(game
+            .gen_all_moves()
+            .unwrap()
+            .iter()
+            .all(|mv| mv.from() == (4, 7)));
+
+        // Castling needs the king and the rook on their starting squares, even with the rights
+        for fen in [
+            "4k3/8/8/8/8/8/8/3K3R w K - 0 1",
+            "4k3/8/8/8/8/8/8/4K3 w K - 0 1",
+            "4k3/8/8/8/8/8/8/4K2r w K - 0 1",
+        ] {
+            let game = Game::from_fen(fen).unwrap();
+            assert!(
+                game.gen_all_moves()
+                    .unwrap_or_default()
+                    .iter()
+                    .all(|mv| !mv.is_castle()),
+                "{}",
+                fen
+            );
+        }
+
+        // A pawn that gave check can be captured en passant
+        let game = Game::from_fen("8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1").unwrap();
+        assert!(game.gen_moves(4, 4).unwrap().contains(&Move::Capture {
+            from: (4, 4),
+            to: (3, 5),
+            capture: (3, 4),
+        }));
+    }
+}
@@ -1,92 +1,32 @@
 /// This file is very messy -.- i know...
 /// Hopefully it's abstracted away enough that no one will need to read this
-use crate::{Color, Game, Move, Piece, PieceType};
+use crate::{
+    chess::attacks::{pawn_attacks, piece_attacks},
+    Color, Game, Move, MoveList, Piece, PieceType,
+};
 
-const ROOK_DIRS: &[(i32, i32)] = &[(1, 0), (-1, 0), (0, 1), (0, -1)];
-const BISHOP_DIRS: &[(i32, i32)] = &[(1, 1), (-1, 1), (1, -1), (-1, -1)];
-const KING_QUEEN_DIRS: &[(i32, i32)] = &[
-    (1, 0),
-    (-1, 0),
-    (0, 1),
-    (0, -1),
-    (1, 1),
-    (-1, 1),
-    (1, -1),
-    (-1, -1),
-];
-const KNIGHT_DIRS: &[(i32, i32)] = &[
-    (2, 1),
-    (2, -1),
-    (-2, 1),
-    (-2, -1),
-    (1, 2),
-    (1, -2),
-    (-1, 2),
-    (-1, -2),
-];
+/// (non attacked positions, empty positions, king end position, rook start position, rook end
+/// position) of a castle
+type CastleTiles = (&'static [usize], &'static [usize], usize, usize, usize);
 
-/// Internal struct used for storing search data about a move
-struct OutSearch<'a> {
-    /// How far out the search can continue
-    depth: i32,
-    /// Direction of the search
-    dirs: &'a [(i32, i32)],
-    /// If quiet moves are allowed
-    quiet: bool,
-    /// If captures are allowed
-    captures: bool,
-}
-const ROOK_OUT_SEARCH: OutSearch = OutSearch {
-    depth: 8,
-    dirs: ROOK_DIRS,
-    quiet: true,
-    captures: true,
-};
-const BISHOP_OUT_SEARCH: OutSearch = OutSearch {
-    depth: 8,
-    dirs: BISHOP_DIRS,
-    quiet: true,
-    captures: true,
-};
-const QUEEN_OUT_SEARCH: OutSearch = OutSearch {
-    depth: 8,
-    dirs: KING_QUEEN_DIRS,
-    quiet: true,
-    captures: true,
-};
-const KNIGHT_OUT_SEARCH: OutSearch = OutSearch {
-    depth: 1,
-    dirs: KNIGHT_DIRS,
-    quiet: true,
-    captures: true,
-};
-const KING_OUT_SEARCH: OutSearch = OutSearch {
-    depth: 1,
-    dirs: KING_QUEEN_DIRS,
-    quiet: true,
-    captures: true,
-};
+const KING_SIDE_CASTLE: CastleTiles = (&[4, 5, 6], &[5, 6], 6, 7, 5);
+const QUEEN_SIDE_CASTLE: CastleTiles = (&[4, 3, 2], &[1, 2, 3], 2, 0, 3);
 
 impl Game {
     /// Generates all pseudo legal moves for a piece
     ///
     /// A pseudo legal move is a move that is legal except for the fact that it might leave the king
-    /// in check.
+    /// in check. The moves are added to the end of `moves`.
     pub(crate) fn gen_pseudo_legal_moves(
         &self,
         x: usize,
         y: usize,
         skip_castle: bool,
-    ) -> Option<Vec<Move>> {
-        let piece = self.board.get_tile(x, y);
-
-        if piece.is_none() {
-            return None;
-        }
-
-        let piece = piece.expect("Already checked that this is not none");
-
-        let mut moves: Vec<Move> = vec![];
+        moves: &mut MoveList,
+    ) {
+        let Some(piece) = self.board.get_tile(x, y) else {
+            return;
+        };
 
         if piece.piece_type == PieceType::Pawn {
             let final_rank = if piece.color == Color::White { 0 } else { 7 };
@@ -104,24 +44,19 @@ impl Game {
                 let c_x = x as i32;
                 let c_y = y as i32 + dir;
 
-                if c_y >= 0 && c_y <= 7 {
+                if (0..=7).contains(&c_y) {
                     let c_x = c_x as usize;
                     let c_y = c_y as usize;
 
-                    let oc_piece = self.board.get_tile(c_x as usize, c_y as usize);
+                    let oc_piece = self.board.get_tile(c_x, c_y);
 
                     if oc_piece.is_none() {
                         if c_y == final_rank {
-                            moves.append(
-                                &mut promotion_pieces
-                                    .iter()
-                                    .map(|&p| Move::QuietPromotion {
-                                        from: (x, y),
-                                        to: (c_x, c_y),
-                                        promotion: p,
-                                    })
-                                    .collect(),
-                            );
+                            moves.extend(promotion_pieces.iter().map(|&p| Move::QuietPromotion {
+                                from: (x, y),
+                                to: (c_x, c_y),
+                                promotion: p,
+                            }));
                         } else {
                             moves.push(Move::Quiet {
                                 from: (x, y),
@@ -137,14 +72,12 @@ impl Game {
                 let c_x = x as i32;
                 let c_y = y as i32 + dir * 2;
 
-                if (c_y >= 0 && c_y <= 7) && y == starting_rank {
+                if (0..=7).contains(&c_y) && y == starting_rank {
                     let c_x = c_x as usize;
                     let c_y = c_y as usize;
 
-                    let oc_piece_further = self.board.get_tile(c_x as usize, c_y);
-                    let oc_piece_close = self
-                        .board
-                        .get_tile(c_x as usize, (c_y as i32 - dir) as usize);
+                    let oc_piece_further = self.board.get_tile(c_x, c_y);
+                    let oc_piece_close = self.board.get_tile(c_x, (c_y as i32 - dir) as usize);
 
                     if oc_piece_further.is_none() && oc_piece_close.is_none() {
                         moves.push(Move::DoublePawnPush {
@@ -157,37 +90,22 @@ impl Game {
 
             // Capture
             {
-                for x_dir in [-1, 1] {
-                    let c_x = x as i32 + x_dir;
-                    let c_y = y as i32 + dir;
-
-                    if c_y >= 0 && c_y <= 7 && c_x >= 0 && c_x <= 7 {
-                        let c_x = c_x as usize;
-                        let c_y = c_y as usize;
+                let enemies = self.board.color_pieces(piece.color.opposite());
 
-                        let oc_piece = self.board.get_tile(c_x as usize, c_y as usize);
-
-                        if oc_piece.is_some() && oc_piece.unwrap().color != piece.color {
-                            if c_y == final_rank {
-                                moves.append(
-                                    &mut promotion_pieces
-                                        .iter()
-                                        .map(|&p| Move::CapturePromotion {
-                                            from: (x, y),
-                                            to: (c_x, c_y),
-                                            capture: (c_x, c_y),
-                                            promotion: p,
-                                        })
-                                        .collect(),
-                                );
-                            } else {
-                                moves.push(Move::Capture {
-                                    from: (x, y),
-                                    to: (c_x, c_y),
-                                    capture: (c_x, c_y),
-                                });
-                            }
-                        }
+                for to in (pawn_attacks((x, y), piece.color) & enemies).squares() {
+                    if to.1 == final_rank {
+                        moves.extend(promotion_pieces.iter().map(|&p| Move::CapturePromotion {
+                            from: (x, y),
+                            to,
+                            capture: to,
+                            promotion: p,
+                        }));
+                    } else {
+                        moves.push(Move::Capture {
+                            from: (x, y),
+                            to,
+                            capture: to,
+                        });
                     }
                 }
             }
@@ -199,7 +117,7 @@ impl Game {
                         let c_x = x as i32 + x_dir;
                         let c_y = y as i32 + dir;
 
-                        if c_y >= 0 && c_y <= 7 && c_x >= 0 && c_x <= 7 {
+                        if (0..=7).contains(&c_y) && (0..=7).contains(&c_x) {
                             let c_x = c_x as usize;
                             let c_y = c_y as usize;
 
@@ -215,89 +133,55 @@ impl Game {
                 }
             }
         } else {
-            let searches = match piece.piece_type {
-                PieceType::Rook => vec![ROOK_OUT_SEARCH],
-                PieceType::Bishop => vec![BISHOP_OUT_SEARCH],
-                PieceType::Queen => vec![QUEEN_OUT_SEARCH],
-                PieceType::Knight => vec![KNIGHT_OUT_SEARCH],
-                PieceType::King => vec![KING_OUT_SEARCH],
-                PieceType::Pawn => unreachable!(),
-            };
-
-            // Regular search out
-            for search in searches {
-                for dir in search.dirs {
-                    for i in 1..=search.depth {
-                        let c_x = dir.0 * i + x as i32;
-                        let c_y = dir.1 * i + y as i32;
-
-                        if c_x < 0 || c_x > 7 || c_y < 0 || c_y > 7 {
-                            break;
-                        }
-
-                        let c_x = c_x as usize;
-                        let c_y = c_y as usize;
-
-                        let oc_piece = self.board.get_tile(c_x, c_y);
-
-                        match oc_piece {
-                            Some(oc_piece) => {
-                                if oc_piece.color != piece.color && search.captures {
-                                    moves.push(Move::Capture {
-                                        from: (x, y),
-                                        to: (c_x, c_y),
-                                        capture: (c_x, c_y),
-                                    });
-                                }
-
-                                break;
-                            }
-                            None => {
-                                if search.quiet {
-                                    moves.push(Move::Quiet {
-                                        from: (x, y),
-                                        to: (c_x, c_y),
-                                    });
-                                }
-                            }
-                        }
-                    }
+            let targets = piece_attacks((x, y), piece, self.board.occupied())
+                & !self.board.color_pieces(piece.color);
+            let enemies = self.board.color_pieces(piece.color.opposite());
+
+            for to in targets.squares() {
+                if enemies.contains(to) {
+                    moves.push(Move::Capture {
+                        from: (x, y),
+                        to,
+                        capture: to,
+                    });
+                } else {
+                    moves.push(Move::Quiet { from: (x, y), to });
                 }
             }
         }
 
         // Castling
-        if piece.piece_type == PieceType::King && !skip_castle {
-            // (non attacked positions, empty positions, king end position, rook start
-            // position, rook end position)
-            let queen_side_tiles: (Vec<usize>, Vec<usize>, usize, usize, usize) =
-                (vec![4, 3, 2], vec![1, 2, 3], 2, 0, 3);
-            let king_side_tiles: (Vec<usize>, Vec<usize>, usize, usize, usize) =
-                (vec![4, 5, 6], vec![5, 6], 6, 7, 5);
-
-            let rank = if piece.color == Color::White { 7 } else { 0 };
+        let rank = if piece.color == Color::White { 7 } else { 0 };
+        if piece.piece_type == PieceType::King && (x, y) == (4, rank) && !skip_castle {
             let (kingside_castle, queenside_castle) = if piece.color == Color::White {
                 (self.white_kingside_castle, self.white_queenside_castle)
             } else {
                 (self.black_kingside_castle, self.black_queenside_castle)
             };
-            let mut check_data = vec![];
-            if kingside_castle {
-                check_data.push(king_side_tiles);
-            }
-            if queenside_castle {
-                check_data.push(queen_side_tiles);
-            }
 
-            for (tiles_not_attacked, tiles_empty, king_start_x, rook_start_x, rook_end_x) in
-                check_data
-            {
-                if tiles_empty
-                    .into_iter()
-                    .all(|x| self.board.get_tile(x, rank).is_none())
+            for (
+                right,
+                (tiles_not_attacked, tiles_empty, king_start_x, rook_start_x, rook_end_x),
+            ) in [
+                (kingside_castle, KING_SIDE_CASTLE),
+                (queenside_castle, QUEEN_SIDE_CASTLE),
+            ] {
+                if !right {
+                    continue;
+                }
+
+                let own_rook = Piece {
+                    piece_type: PieceType::Rook,
+                    color: piece.color,
+                };
+
+                if self.board.get_tile(rook_start_x, rank) == Some(own_rook)
+                    && tiles_empty
+                        .iter()
+                        .all(|&x| self.board.get_tile(x, rank).is_none())
                     && tiles_not_attacked
-                        .into_iter()
-                        .all(|x| !tile_under_attack(&self, x, rank, piece.color.opposite()))
+                        .iter()
+                        .all(|&x| !self.board.is_attacked((x, rank), piece.color.opposite()))
                 {
                     moves.push(Move::Castle {
                         from: (4, rank),
@@ -308,61 +192,5 @@ impl Game {
                 }
             }
         }
-
-        Some(moves)
     }
 }
-
-/// Checks if a tile is attacked by a certain color
-///
-/// # Arguments
-/// * `x` - The x coordinate of the tile
-/// * `y` - The y coordinate of the tile
-/// * `color` - The color of the attacking pieces
-///
-/// # Returns
-/// * `bool` - If theres a piece that can immediately capture the tile
-fn tile_under_attack(board: &Game, x: usize, y: usize, color: Color) -> bool {
-    // The idea here is to create a dummy board and on the tile we want to check add a piece
-    // Then we run move generation and check if any of the moves are a capture of the tile
-    // If so then the tile is under attack
-    let mut dummy_board = board.clone();
-
-    if let Some(piece) = dummy_board.board.get_tile(x, y) {
-        if piece.color == color {
-            return false;
-        }
-    } else {
-        dummy_board.board.set_tile(
-            x,
-            y,
-            Piece {
-                piece_type: PieceType::Pawn,
-                color: color.opposite(),
-            },
-        );
-    }
-
-    dummy_board
-        .board
-        .tiles
-        .iter()
-        .enumerate()
-        .filter(|(_, p)| p.is_some())
-        .map(|(i, p)| {
-            let x = i % 8;
-            let y = i / 8;
-
-            ((x, y), p.unwrap())
-        })
-        .filter(|(_, p)| p.color == color)
-        .map(|((x, y), _)| dummy_board.gen_pseudo_legal_moves(x, y, true))
-        .flatten()
-        .flatten()
-        .any(|m| match m {
-            Move::Capture { capture, .. } | Move::CapturePromotion { capture, .. } => {
-                capture == (x, y)
-            }
-            _ => false,
-        })
-}
@@ -0,0 +1,131 @@
+use crate::{Bitboard, Color, Game, PieceType};
+
+/// All light squares, a8 is a light square and it's bit 0
+const LIGHT_SQUARES: Bitboard = Bitboard(0xaa55_aa55_aa55_aa55);
+
+impl Game {
+    /// Returns if neither player has enough material to checkmate
+    ///
+    /// This covers FIDE's dead positions that only depend on material, king against king, king
+    /// and a minor piece against king and kings with bishops that are all on the same colour.
+    ///
+    /// # Returns
+    /// * `bool` - If the game is drawn because no sequence of moves can end in checkmate
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::Game;
+    ///
+    /// let game = Game::from_fen("4k3/8/8/8/8/8/8/4KN2 w - - 0 1").unwrap();
+    /// assert!(game.is_insufficient_material());
+    /// ```
+    pub fn is_insufficient_material(&self) -> bool {
+        self.has_insufficient_material(Color::White) && self.has_insufficient_material(Color::Black)
+    }
+
+    /// Returns if a color can't checkmate the other color no matter how the other color plays
+    ///
+    /// This is what decides timeouts, if the player whose time ran out has an opponent that can't
+    /// win, the game is drawn.
+    ///
+    /// # Arguments
+    /// * `color` - The color to check
+    ///
+    /// # Returns
+    /// * `bool` - If `color` doesn't have enough material to checkmate
+    pub fn has_insufficient_material(&self, color: Color) -> bool {
+        let board = &self.board;
+        let own = board.color_pieces(color);
+        let other = board.color_pieces(color.opposite());
+
+        let heavy = board.type_pieces(PieceType::Pawn)
+            | board.type_pieces(PieceType::Rook)
+            | board.type_pieces(PieceType::Queen);
+
+        if !(own & heavy).is_empty() {
+            return false;
+        }
+
+        let knights = board.type_pieces(PieceType::Knight);
+        let bishops = board.type_pieces(PieceType::Bishop);
+
+        if !(own & knights).is_empty() {
+            // A lone knight can only mate if the other color has pieces that can block the king
+            let blockers =
+                other & !board.type_pieces(PieceType::King) & !board.type_pieces(PieceType::Queen);
+
+            return own.count() <= 2 && blockers.is_empty();
+        }
+
+        if !(own & bishops).is_empty() {
+            // Bishops can only mate with help if there are bishops on both square colours or
+            // there are pieces that can block the king
+            let same_square_color =
+                (bishops & LIGHT_SQUARES).is_empty() || (bishops & !LIGHT_SQUARES).is_empty();
+
+            return same_square_color
+                && board.type_pieces(PieceType::Pawn).is_empty()
+                && knights.is_empty();
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn should_detect_insufficient_material() {
+        let fens_to_test = vec![
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/4KB2 w - - 0 1",
+            "4k3/8/8/8/8/8/8/4KN2 b - - 0 1",
+            "4kb2/8/8/8/8/8/8/2B1K3 w - - 0 1",
+            "3bkb2/8/8/8/8/8/8/2B1K1B1 w - - 0 1",
+        ];
+
+        for fen in fens_to_test {
+            assert!(
+                Game::from_fen(fen).unwrap().is_insufficient_material(),
+                "{}",
+                fen
+            );
+        }
+    }
+
+    #[test]
+    pub fn should_detect_sufficient_material() {
+        let fens_to_test = vec![
+            "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/4KR2 w - - 0 1",
+            "4k3/8/8/8/8/8/8/3QK3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/3NKN2 w - - 0 1",
+            "4kn2/8/8/8/8/8/8/3NK3 w - - 0 1",
+            "4kb2/8/8/8/8/8/8/3BK3 w - - 0 1",
+            "4kn2/8/8/8/8/8/8/2B1K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/2B1KB2 w - - 0 1",
+        ];
+
+        for fen in fens_to_test {
+            assert!(
+                !Game::from_fen(fen).unwrap().is_insufficient_material(),
+                "{}",
+                fen
+            );
+        }
+    }
+
+    #[test]
+    pub fn should_check_material_per_color() {
+        // White can never mate with only a knight, but black's queen can
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/3qKN2 w - - 0 1").unwrap();
+        assert!(game.has_insufficient_material(Color::White));
+        assert!(!game.has_insufficient_material(Color::Black));
+
+        // A knight can mate a king that is boxed in by its own rook
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/3rKN2 w - - 0 1").unwrap();
+        assert!(!game.has_insufficient_material(Color::White));
+    }
+}
@@ -1,9 +1,19 @@
-use crate::{Board, Color, PieceType};
+use crate::{Board, Color};
 
 mod apply_move;
 mod fen;
+mod gen_legal_moves;
 mod gen_pseudo_legal_moves;
-use super::Move;
+mod material;
+mod perft;
+pub use perft::PerftTable;
+mod san;
+mod uci;
+mod undo;
+mod validate;
+pub use undo::UndoInfo;
+mod zobrist;
+use super::{Move, MoveList, Square};
 
 /// A game of chess
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
@@ -17,12 +27,20 @@ pub struct Game {
     white_queenside_castle: bool,
     black_kingside_castle: bool,
     black_queenside_castle: bool,
+
+    /// Number of halfmoves since the last capture or pawn move
+    halfmove_clock: u32,
+    /// Number of the current full move, starts at 1 and is incremented after black's move
+    fullmove_number: u32,
+
+    /// Zobrist hash of the position, updated incrementally when moves are applied
+    hash: u64,
 }
 
 impl Game {
     /// Returns a game with the starting position
     pub fn start_pos() -> Game {
-        Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq -")
+        Game::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")
             .expect("This fen string is valid")
     }
 
@@ -49,16 +67,34 @@ impl Game {
         self.en_passant = None;
 
         self.board = board;
+        self.hash = self.compute_hash();
     }
 
     /// Returns the current turn
     ///
     /// # Return
     /// * `Color` - The current turn
-    pub fn get_turn(&mut self) -> Color {
+    pub fn get_turn(&self) -> Color {
         self.turn
     }
 
+    /// Returns the halfmove clock
+    ///
+    /// # Return
+    /// * `u32` - The number of halfmoves since the last capture or pawn move
+    pub fn get_halfmove_clock(&self) -> u32 {
+        self.halfmove_clock
+    }
+
+    /// Returns the fullmove number
+    ///
+    /// # Return
+    /// * `u32` - The number of the current full move, it starts at 1 and is incremented after
+    ///   every move by black
+    pub fn get_fullmove_number(&self) -> u32 {
+        self.fullmove_number
+    }
+
     /// Sets the current turn
     ///
     /// **This will reset en passant**
@@ -68,44 +104,47 @@ impl Game {
     pub fn set_turn(&mut self, turn: Color) {
         self.en_passant = None;
         self.turn = turn;
+        self.hash = self.compute_hash();
     }
 
     /// Returns if a certain color can capture the other color's king
     fn can_capture_king(&self, color: Color) -> bool {
         self.board
-            .tiles
-            .iter()
-            .enumerate()
-            .filter(|(_, p)| p.is_some())
-            .map(|(i, p)| {
-                let x = i % 8;
-                let y = i / 8;
-
-                ((x, y), p.unwrap())
-            })
-            .filter(|(_, p)| p.color == color)
-            .map(|((x, y), _)| {
-                self.gen_pseudo_legal_moves(x, y, false)
-                    .unwrap_or(Vec::new())
-            })
+            .get_king_pos(color.opposite())
+            .is_some_and(|king_pos| self.board.is_attacked(king_pos, color))
+    }
+
+    /// Returns the en passant pawn, but only if it can actually be captured by a legal move
+    fn capturable_en_passant(&self) -> Option<(usize, usize)> {
+        let (ep_x, ep_y) = self.en_passant?;
+
+        [ep_x.checked_sub(1), Some(ep_x + 1).filter(|x| *x < 8)]
+            .into_iter()
             .flatten()
-            .any(|m| match m {
-                Move::Capture { capture, .. } | Move::CapturePromotion { capture, .. } => {
-                    self.board
-                        .get_tile(capture.0, capture.1)
-                        .unwrap()
-                        .piece_type
-                        == PieceType::King
-                }
-                _ => false,
-            })
+            .flat_map(|x| self.gen_moves(x, ep_y).unwrap_or_default())
+            .any(|mv| mv.capture() == Some((ep_x, ep_y)))
+            .then_some((ep_x, ep_y))
+    }
+
+    /// Returns if two games are in the same position by FIDE's definition
+    ///
+    /// The pieces, the turn, the castling rights and the en passant pawn have to be the same. An
+    /// en passant pawn only matters if it can actually be captured.
+    pub(crate) fn is_same_position(&self, other: &Game) -> bool {
+        self.board == other.board
+            && self.turn == other.turn
+            && self.white_kingside_castle == other.white_kingside_castle
+            && self.white_queenside_castle == other.white_queenside_castle
+            && self.black_kingside_castle == other.black_kingside_castle
+            && self.black_queenside_castle == other.black_queenside_castle
+            && self.capturable_en_passant() == other.capturable_en_passant()
     }
 
     /// Returns if the current turn is in check
     ///
     /// # Returns
     /// * `bool` - If the current turn is in check, if it's black to move and black is in check,
-    /// this will return true
+    ///   this will return true
     pub fn is_check(&self) -> bool {
         self.can_capture_king(self.turn.opposite())
     }
@@ -114,7 +153,7 @@ impl Game {
     ///
     /// # Returns
     /// * `bool` - If the current turn is in checkmate, if it's black to move and black is in
-    /// checkmate, this will return true
+    ///   checkmate, this will return true
     pub fn is_checkmate(&self) -> bool {
         self.is_check() && self.gen_all_moves().is_none()
     }
@@ -123,33 +162,62 @@ impl Game {
     ///
     /// # Returns
     /// * `bool` - If the current turn is in stalemate, if it's black to move and black is in
-    /// stalemate, this will return true
+    ///   stalemate, this will return true
     pub fn is_stalemate(&self) -> bool {
         !self.is_check() && self.gen_all_moves().is_none()
     }
 
+    /// Returns if the current turn can claim a draw by the fifty-move rule
+    ///
+    /// # Returns
+    /// * `bool` - If no capture or pawn move has been made in the last fifty moves by each player
+    pub fn can_claim_fifty_move_draw(&self) -> bool {
+        self.halfmove_clock >= 100
+    }
+
+    /// Returns if the game is drawn by the seventy-five-move rule
+    ///
+    /// Unlike the fifty-move rule this draw doesn't have to be claimed, but if the last move
+    /// checkmated the checkmate counts instead.
+    ///
+    /// # Returns
+    /// * `bool` - If no capture or pawn move has been made in the last seventy-five moves by each
+    ///   player and the current turn isn't checkmated
+    pub fn is_seventy_five_move_draw(&self) -> bool {
+        self.halfmove_clock >= 150 && !self.is_checkmate()
+    }
+
+    /// Generates all moves for the current turn into a move list without allocating
+    ///
+    /// The list is cleared first. The moves are the same, and in the same order, as the moves
+    /// from `gen_all_moves`.
+    ///
+    /// # Arguments
+    /// * `moves` - The list to generate the moves into
+    pub fn generate_into(&self, moves: &mut MoveList) {
+        moves.clear();
+
+        let legality = self.legality();
+
+        for (x, y) in self.board.color_pieces(self.turn).squares() {
+            self.gen_legal_moves(x, y, &legality, moves);
+        }
+    }
+
     /// Returns all moves for the current turn
     ///
     /// # Returns
     /// * `Option<Vec<Move>>` - A vector of all the moves for the current turn, if there are no
-    /// moves, this will return None
+    ///   moves, this will return None
     pub fn gen_all_moves(&self) -> Option<Vec<Move>> {
-        let moves = (0..64)
-            .map(|i| {
-                let x = i % 8;
-                let y = i / 8;
-
-                (x, y)
-            })
-            .map(|(x, y)| self.gen_moves(x, y).unwrap_or(Vec::new()))
-            .flatten()
-            .collect::<Vec<Move>>();
+        let mut moves = MoveList::new();
+        self.generate_into(&mut moves);
 
-        if moves.len() == 0 {
+        if moves.is_empty() {
             return None;
         }
 
-        Some(moves)
+        Some(moves.to_vec())
     }
 
     /// Returns all moves that can be made from a square to a square
@@ -160,7 +228,7 @@ impl Game {
     ///
     /// # Returns
     /// * `Option<Vec<Move>>` - A vector of all the possible moves, if there are no moves, this
-    /// will return None.
+    ///   will return None.
     pub fn get_move(&self, from: (usize, usize), to: (usize, usize)) -> Option<Vec<Move>> {
         self.gen_moves(from.0, from.1).map(|mvs| {
             mvs.into_iter()
@@ -169,6 +237,33 @@ impl Game {
         })
     }
 
+    /// Returns all moves that can be made from a square to a square
+    ///
+    /// The same as `get_move`, but with `Square`s.
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::Game;
+    ///
+    /// let game = Game::start_pos();
+    /// let moves = game
+    ///     .moves_between("g1".parse().unwrap(), "f3".parse().unwrap())
+    ///     .unwrap();
+    ///
+    /// assert_eq!(moves.len(), 1);
+    /// assert_eq!(moves[0].uci(), Some("g1f3".to_string()));
+    /// ```
+    pub fn moves_between(&self, from: Square, to: Square) -> Option<Vec<Move>> {
+        self.get_move(from.into(), to.into())
+    }
+
+    /// Returns all moves for a piece on a square
+    ///
+    /// The same as `gen_moves`, but with a `Square`.
+    pub fn moves_from(&self, square: Square) -> Option<Vec<Move>> {
+        self.gen_moves(square.x(), square.y())
+    }
+
     /// Returns all moves for a certain tile and the current turn
     ///
     /// # Arguments
@@ -177,9 +272,11 @@ impl Game {
     ///
     /// # Returns
     /// * `Option<Vec<Move>>` - A vector of all the moves for the tile, if there are no moves, this
-    /// will return None. If the piece of x and y is the opposite color of the current turn, this
-    /// will return None
+    ///   will return None. If the piece of x and y is the opposite color of the current turn or the
+    ///   coordinates are outside of the board, this will return None
     pub fn gen_moves(&self, x: usize, y: usize) -> Option<Vec<Move>> {
+        Square::try_from((x, y)).ok()?;
+
         if let Some(piece) = self.board.get_tile(x, y) {
             if piece.color != self.turn {
                 return None;
@@ -188,29 +285,56 @@ impl Game {
             return None;
         }
 
-        let moves = self.gen_pseudo_legal_moves(x, y, false);
+        let mut moves = MoveList::new();
+        self.gen_legal_moves(x, y, &self.legality(), &mut moves);
 
-        if moves.is_none() {
+        if moves.is_empty() {
             return None;
         }
 
-        let moves = moves
-            .unwrap()
-            .into_iter()
-            .filter(|m| {
-                let mut game = self.clone();
-                game.apply_move(*m)
-                    .expect("gen_pseudo_legal_moves only returns valid moves");
+        Some(moves.to_vec())
+    }
+}
 
-                let can_cap = !game.can_capture_king(game.turn);
-                can_cap
-            })
-            .collect::<Vec<Move>>();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        if moves.len() == 0 {
-            return None;
-        }
+    #[test]
+    pub fn should_detect_move_rule_draws() {
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/4K2R w - - 99 80").unwrap();
+        assert!(!game.can_claim_fifty_move_draw());
+
+        let mut game_after = game;
+        game_after
+            .apply_move(game.parse_san("Rh7").unwrap())
+            .unwrap();
+        assert!(game_after.can_claim_fifty_move_draw());
+        assert!(!game_after.is_seventy_five_move_draw());
+
+        // A pawn move or capture resets the counter
+        let mut game = Game::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 120 80").unwrap();
+        assert!(game.can_claim_fifty_move_draw());
+        game.apply_move(game.parse_san("e4").unwrap()).unwrap();
+        assert!(!game.can_claim_fifty_move_draw());
+
+        let game = Game::from_fen("4k3/8/8/8/8/8/8/4K2R b - - 150 80").unwrap();
+        assert!(game.is_seventy_five_move_draw());
+    }
+
+    #[test]
+    pub fn should_not_generate_moves_outside_of_the_board() {
+        let game = Game::start_pos();
+        assert_eq!(game.gen_moves(8, 6), None);
+        assert_eq!(game.gen_moves(4, usize::MAX), None);
+        assert_eq!(game.get_move((9, 9), (4, 4)), None);
+    }
 
-        Some(moves)
+    #[test]
+    pub fn checkmate_should_beat_the_seventy_five_move_rule() {
+        let game = Game::from_fen("R3k3/8/4K3/8/8/8/8/8 b - - 150 80").unwrap();
+        assert!(game.is_checkmate());
+        assert!(game.can_claim_fifty_move_draw());
+        assert!(!game.is_seventy_five_move_draw());
     }
 }
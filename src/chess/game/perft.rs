@@ -0,0 +1,275 @@
+use std::{
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    thread,
+};
+
+use crate::{Game, Move, MoveList};
+
+/// A hash table of perft counts that can be shared between threads
+///
+/// The counts are stored by the Zobrist key of the position and the depth, so transpositions
+/// are only counted once. Every entry is two atomics, the key is stored xor'ed with the data so
+/// an entry that is torn by two threads writing at once is never read back.
+pub struct PerftTable {
+    entries: Vec<[AtomicU64; 2]>,
+}
+
+impl PerftTable {
+    /// Creates an empty table
+    ///
+    /// # Arguments
+    /// * `size_mb` - The size of the table in megabytes, it's rounded down to a power of two
+    ///   number of entries
+    pub fn new(size_mb: usize) -> PerftTable {
+        let entry_size = std::mem::size_of::<[AtomicU64; 2]>();
+        let len = (size_mb * 1024 * 1024 / entry_size).max(1);
+        // Round down to a power of two so the index is a mask of the key
+        let len = 1 << len.ilog2();
+
+        PerftTable {
+            entries: (0..len)
+                .map(|_| [AtomicU64::new(0), AtomicU64::new(0)])
+                .collect(),
+        }
+    }
+
+    /// Returns the count of a position at a depth, if it's in the table
+    fn get(&self, key: u64, depth: u8) -> Option<u64> {
+        let [check, data] = &self.entries[key as usize & (self.entries.len() - 1)];
+        let data = data.load(Ordering::Relaxed);
+
+        (check.load(Ordering::Relaxed) ^ data == key && data & 0xff == depth as u64)
+            .then_some(data >> 8)
+    }
+
+    /// Stores the count of a position at a depth, replacing what was in the entry
+    fn insert(&self, key: u64, depth: u8, nodes: u64) {
+        let [check, data] = &self.entries[key as usize & (self.entries.len() - 1)];
+        let value = nodes << 8 | depth as u64;
+
+        check.store(key ^ value, Ordering::Relaxed);
+        data.store(value, Ordering::Relaxed);
+    }
+}
+
+impl Game {
+    /// Counts the leaf nodes of the move tree to a certain depth
+    ///
+    /// Perft is the standard way to test a move generator, the counts for many positions are
+    /// known and can be compared with other engines. At the last ply the moves are only counted,
+    /// not made.
+    ///
+    /// # Arguments
+    /// * `depth` - The number of plies to search
+    ///
+    /// # Returns
+    /// * `u64` - The number of positions at `depth` plies from the current position
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::Game;
+    ///
+    /// let game = Game::start_pos();
+    /// assert_eq!(game.perft(3), 8902);
+    /// ```
+    pub fn perft(&self, depth: u8) -> u64 {
+        let mut game = *self;
+        perft_recursive(&mut game, depth)
+    }
+
+    /// Counts the leaf nodes of the move tree below every legal move
+    ///
+    /// When perft gives the wrong count, comparing the divide of two engines shows which move
+    /// has the wrong subtree.
+    ///
+    /// # Arguments
+    /// * `depth` - The number of plies to search, the legal move itself is the first ply
+    ///
+    /// # Returns
+    /// * `Vec<(Move, u64)>` - Every legal move together with its perft at `depth - 1`, in the same
+    ///   order as `gen_all_moves`. If `depth` is 0 the vector is empty
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::Game;
+    ///
+    /// let game = Game::start_pos();
+    /// let divide = game.divide(2);
+    ///
+    /// assert_eq!(divide.len(), 20);
+    /// assert!(divide.iter().all(|(_, nodes)| *nodes == 20));
+    /// ```
+    pub fn divide(&self, depth: u8) -> Vec<(Move, u64)> {
+        if depth == 0 {
+            return vec![];
+        }
+
+        let mut game = *self;
+        let mut moves = MoveList::new();
+        game.generate_into(&mut moves);
+
+        moves
+            .iter()
+            .map(|&mv| {
+                let undo = game.make_move(mv);
+                let nodes = perft_recursive(&mut game, depth - 1);
+                game.unmake_move(mv, undo);
+
+                (mv, nodes)
+            })
+            .collect()
+    }
+
+    /// Counts the leaf nodes of the move tree with several threads
+    ///
+    /// The moves of the current position are split between the threads, and a `PerftTable` can
+    /// be shared between them to skip positions that have already been counted. The result is
+    /// the same as `perft`.
+    ///
+    /// # Arguments
+    /// * `depth` - The number of plies to search
+    /// * `threads` - The number of threads to use, 0 is treated as 1
+    /// * `table` - A table to cache counts in, it can be reused between calls
+    ///
+    /// # Returns
+    /// * `u64` - The number of positions at `depth` plies from the current position
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Game, PerftTable};
+    ///
+    /// let game = Game::start_pos();
+    /// let table = PerftTable::new(16);
+    ///
+    /// assert_eq!(game.perft_parallel(4, 4, Some(&table)), 197281);
+    /// assert_eq!(game.perft_parallel(4, 4, None), 197281);
+    /// ```
+    pub fn perft_parallel(&self, depth: u8, threads: usize, table: Option<&PerftTable>) -> u64 {
+        if depth <= 1 {
+            return self.perft(depth);
+        }
+
+        let mut moves = MoveList::new();
+        self.generate_into(&mut moves);
+
+        // Every thread takes the next root move that no thread has started on
+        let next = AtomicUsize::new(0);
+        let nodes = AtomicU64::new(0);
+
+        thread::scope(|scope| {
+            for _ in 0..threads.max(1) {
+                scope.spawn(|| {
+                    let mut game = *self;
+
+                    while let Some(&mv) = moves.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        let undo = game.make_move(mv);
+                        let count = match table {
+                            Some(table) => perft_hashed(&mut game, depth - 1, table),
+                            None => perft_recursive(&mut game, depth - 1),
+                        };
+                        game.unmake_move(mv, undo);
+
+                        nodes.fetch_add(count, Ordering::Relaxed);
+                    }
+                });
+            }
+        });
+
+        nodes.into_inner()
+    }
+}
+
+/// Internal helper that walks the move tree like `perft_recursive`, but looks up and stores the
+/// counts in a table
+fn perft_hashed(game: &mut Game, depth: u8, table: &PerftTable) -> u64 {
+    // Counting the leaves is cheaper than a table lookup
+    if depth <= 1 {
+        return perft_recursive(game, depth);
+    }
+
+    if let Some(nodes) = table.get(game.hash_key(), depth) {
+        return nodes;
+    }
+
+    let mut moves = MoveList::new();
+    game.generate_into(&mut moves);
+
+    let mut nodes = 0;
+    for &mv in &moves {
+        let undo = game.make_move(mv);
+        nodes += perft_hashed(game, depth - 1, table);
+        game.unmake_move(mv, undo);
+    }
+
+    table.insert(game.hash_key(), depth, nodes);
+
+    nodes
+}
+
+/// Internal helper that walks the move tree with make and unmake
+fn perft_recursive(game: &mut Game, depth: u8) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+
+    let mut moves = MoveList::new();
+    game.generate_into(&mut moves);
+
+    // Bulk counting, the moves at the last ply don't have to be made
+    if depth == 1 {
+        return moves.len() as u64;
+    }
+
+    let mut nodes = 0;
+    for &mv in &moves {
+        let undo = game.make_move(mv);
+        nodes += perft_recursive(game, depth - 1);
+        game.unmake_move(mv, undo);
+    }
+
+    nodes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn divide_should_add_up_to_perft() {
+        let game =
+            Game::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+
+        let divide = game.divide(2);
+        assert_eq!(divide.len(), 48);
+        assert_eq!(divide.iter().map(|(_, nodes)| nodes).sum::<u64>(), 2039);
+        assert_eq!(game.perft(2), 2039);
+
+        assert_eq!(game.perft(0), 1);
+        assert!(game.divide(0).is_empty());
+    }
+
+    #[test]
+    pub fn perft_parallel_should_be_same_as_perft() {
+        let table = PerftTable::new(1);
+
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 0 1",
+        ] {
+            let game = Game::from_fen(fen).unwrap();
+
+            for depth in 0..=3 {
+                let nodes = game.perft(depth);
+                assert_eq!(game.perft_parallel(depth, 3, None), nodes, "{}", fen);
+                assert_eq!(
+                    game.perft_parallel(depth, 3, Some(&table)),
+                    nodes,
+                    "{}",
+                    fen
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,379 @@
+use crate::{
+    chess::notation::{file_char, parse_file, parse_rank, parse_square, rank_char, square_name},
+    error::ParseSanError,
+    Game, Move, PieceType,
+};
+
+impl Game {
+    /// Returns a move in Standard Algebraic Notation (SAN)
+    ///
+    /// The move has to be a legal move for the current position, since SAN depends on which other
+    /// moves are possible (disambiguation) and on the position after the move (check and mate).
+    ///
+    /// # Arguments
+    /// * `mv` - The move to convert
+    ///
+    /// # Returns
+    /// * `Option<String>` - The move in SAN, e.g. `"Nbd7"`, `"exd8=Q+"` or `"O-O-O#"`. If the move
+    ///   isn't legal for the game or has squares outside of the board, this will return None
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Game, Move};
+    ///
+    /// let game = Game::start_pos();
+    /// let san = game.san(Move::Quiet { from: (6, 7), to: (5, 5) });
+    /// assert_eq!(san, Some("Nf3".to_string()));
+    /// ```
+    pub fn san(&self, mv: Move) -> Option<String> {
+        mv.from_square().ok()?;
+        mv.to_square().ok()?;
+
+        let (from_x, from_y) = mv.from();
+
+        if !self.gen_moves(from_x, from_y)?.contains(&mv) {
+            return None;
+        }
+
+        let piece = self.board.get_tile(from_x, from_y)?;
+
+        let mut san = if mv.is_king_side_castle() {
+            "O-O".to_string()
+        } else if mv.is_queen_side_castle() {
+            "O-O-O".to_string()
+        } else {
+            let mut san = String::new();
+
+            if piece.piece_type == PieceType::Pawn {
+                if mv.is_capture() {
+                    san.push(file_char(from_x));
+                }
+            } else {
+                san.push(piece_letter(piece.piece_type));
+
+                // Other pieces of the same type that can also reach the to square
+                let others = self
+                    .gen_all_moves()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter(|other| other.to() == mv.to() && other.from() != mv.from())
+                    .filter(|other| {
+                        let (x, y) = other.from();
+                        self.board
+                            .get_tile(x, y)
+                            .is_some_and(|p| p.piece_type == piece.piece_type)
+                    })
+                    .map(|other| other.from())
+                    .collect::<Vec<(usize, usize)>>();
+
+                if !others.is_empty() {
+                    if others.iter().all(|(x, _)| *x != from_x) {
+                        san.push(file_char(from_x));
+                    } else if others.iter().all(|(_, y)| *y != from_y) {
+                        san.push(rank_char(from_y));
+                    } else {
+                        san.push_str(&square_name((from_x, from_y)));
+                    }
+                }
+            }
+
+            if mv.is_capture() {
+                san.push('x');
+            }
+
+            san.push_str(&square_name(mv.to()));
+
+            if let Some(promotion) = mv.promotion() {
+                san.push('=');
+                san.push(piece_letter(promotion));
+            }
+
+            san
+        };
+
+        let mut game = *self;
+        game.apply_move_unchecked(mv);
+
+        if game.is_checkmate() {
+            san.push('#');
+        } else if game.is_check() {
+            san.push('+');
+        }
+
+        Some(san)
+    }
+
+    /// Parses a move in Standard Algebraic Notation (SAN) into the matching legal move
+    ///
+    /// The parser is lenient, check and mate suffixes and annotations like `!?` are ignored, castling
+    /// can be written with zeros (`"0-0"`) and the promotion `=` can be left out (`"e8Q"`).
+    ///
+    /// # Arguments
+    /// * `san` - The move in SAN
+    ///
+    /// # Returns
+    /// * `Result<Move, ParseSanError>` - The legal move that the SAN describes, or an error if the
+    ///   string couldn't be parsed or doesn't match exactly one legal move
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Game, Move};
+    ///
+    /// let game = Game::start_pos();
+    /// let mv = game.parse_san("e4").unwrap();
+    /// assert_eq!(mv, Move::DoublePawnPush { from: (4, 6), to: (4, 4) });
+    /// ```
+    pub fn parse_san(&self, san: &str) -> Result<Move, ParseSanError> {
+        let san = san.trim().trim_end_matches(['+', '#', '!', '?']);
+
+        if san.is_empty() {
+            return Err(ParseSanError::Empty);
+        }
+
+        let moves = self.gen_all_moves().unwrap_or_default();
+
+        if matches!(san, "O-O" | "0-0" | "O-O-O" | "0-0-0") {
+            let king_side = san.len() == 3;
+
+            return moves
+                .into_iter()
+                .find(|mv| {
+                    if king_side {
+                        mv.is_king_side_castle()
+                    } else {
+                        mv.is_queen_side_castle()
+                    }
+                })
+                .ok_or(ParseSanError::IllegalMove);
+        }
+
+        let mut chars = san.chars().collect::<Vec<char>>();
+
+        let piece_type = match chars.first().and_then(|&c| letter_piece(c)) {
+            Some(piece_type) => {
+                chars.remove(0);
+                piece_type
+            }
+            None => PieceType::Pawn,
+        };
+
+        // Promotion, either "=Q" or just "Q" at the end
+        let promotion = match chars
+            .last()
+            .and_then(|&c| letter_piece(c.to_ascii_uppercase()))
+        {
+            Some(promotion)
+                if chars.len() > 2
+                    && (chars[chars.len() - 2] == '='
+                        || parse_rank(chars[chars.len() - 2]).is_some()) =>
+            {
+                chars.pop();
+                if chars.last() == Some(&'=') {
+                    chars.pop();
+                }
+                Some(promotion)
+            }
+            _ => None,
+        };
+
+        if chars.len() < 2 {
+            return Err(ParseSanError::InvalidSyntax);
+        }
+
+        let to = parse_square(&chars[chars.len() - 2..].iter().collect::<String>())
+            .ok_or(ParseSanError::InvalidSyntax)?;
+        chars.truncate(chars.len() - 2);
+
+        if matches!(chars.last(), Some('x') | Some(':') | Some('-')) {
+            chars.pop();
+        }
+
+        // Whatever is left has to be disambiguation, a file, a rank or both
+        let (from_file, from_rank) = match chars.as_slice() {
+            [] => (None, None),
+            [c] => match (parse_file(*c), parse_rank(*c)) {
+                (Some(x), _) => (Some(x), None),
+                (_, Some(y)) => (None, Some(y)),
+                _ => return Err(ParseSanError::InvalidSyntax),
+            },
+            [f, r] => (
+                Some(parse_file(*f).ok_or(ParseSanError::InvalidSyntax)?),
+                Some(parse_rank(*r).ok_or(ParseSanError::InvalidSyntax)?),
+            ),
+            _ => return Err(ParseSanError::InvalidSyntax),
+        };
+
+        let mut candidates = moves.into_iter().filter(|mv| {
+            let (x, y) = mv.from();
+
+            mv.to() == to
+                && !mv.is_castle()
+                && mv.promotion() == promotion
+                && from_file.is_none_or(|f| f == x)
+                && from_rank.is_none_or(|r| r == y)
+                && self
+                    .board
+                    .get_tile(x, y)
+                    .is_some_and(|p| p.piece_type == piece_type)
+        });
+
+        let mv = candidates.next().ok_or(ParseSanError::IllegalMove)?;
+
+        if candidates.next().is_some() {
+            return Err(ParseSanError::AmbiguousMove);
+        }
+
+        Ok(mv)
+    }
+}
+
+/// Returns the SAN letter of a piece type, pawns don't have a letter in SAN so they get 'P'
+fn piece_letter(piece_type: PieceType) -> char {
+    match piece_type {
+        PieceType::Pawn => 'P',
+        PieceType::Knight => 'N',
+        PieceType::Bishop => 'B',
+        PieceType::Rook => 'R',
+        PieceType::Queen => 'Q',
+        PieceType::King => 'K',
+    }
+}
+
+/// Returns the piece type of a SAN letter, pawns are never written with a letter
+fn letter_piece(c: char) -> Option<PieceType> {
+    match c {
+        'N' => Some(PieceType::Knight),
+        'B' => Some(PieceType::Bishop),
+        'R' => Some(PieceType::Rook),
+        'Q' => Some(PieceType::Queen),
+        'K' => Some(PieceType::King),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Converts every legal move to SAN and parses it back
+    fn assert_round_trip(game: &Game) {
+        for mv in game.gen_all_moves().unwrap_or_default() {
+            let san = game.san(mv).unwrap();
+            assert_eq!(game.parse_san(&san).unwrap(), mv, "{}", san);
+        }
+    }
+
+    #[test]
+    pub fn san_should_round_trip() {
+        let fens_to_test = vec![
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "R6R/8/8/8/8/8/2k5/R3K2R w - - 0 1",
+        ];
+
+        for fen in fens_to_test {
+            assert_round_trip(&Game::from_fen(fen).unwrap());
+        }
+    }
+
+    #[test]
+    pub fn san_should_disambiguate() {
+        let game = Game::from_fen("4k3/8/8/8/8/8/4K3/R6R w - - 0 1").unwrap();
+        assert_eq!(
+            game.san(game.get_move((0, 7), (3, 7)).unwrap()[0]),
+            Some("Rad1".to_string())
+        );
+
+        let game = Game::from_fen("4k3/8/8/R7/8/8/8/R3K3 w - - 0 1").unwrap();
+        assert_eq!(
+            game.san(game.get_move((0, 3), (0, 5)).unwrap()[0]),
+            Some("R5a3".to_string())
+        );
+
+        let game = Game::from_fen("6k1/8/8/8/Q2Q4/8/8/Q3K3 w - - 0 1").unwrap();
+        assert_eq!(
+            game.san(game.get_move((0, 4), (3, 7)).unwrap()[0]),
+            Some("Qa4d1".to_string())
+        );
+    }
+
+    #[test]
+    pub fn san_should_have_suffixes() {
+        let game = Game::from_fen("r3k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        let mv = game
+            .gen_all_moves()
+            .unwrap()
+            .into_iter()
+            .find(|mv| mv.is_capture() && mv.promotion() == Some(PieceType::Queen))
+            .unwrap();
+        assert_eq!(game.san(mv), Some("bxa8=Q+".to_string()));
+
+        let game = Game::from_fen("6k1/5ppp/8/8/8/8/8/R3K3 w Q - 0 1").unwrap();
+        assert_eq!(
+            game.parse_san("Ra8").unwrap(),
+            game.parse_san("Ra8#").unwrap()
+        );
+        assert_eq!(
+            game.san(game.parse_san("Ra8").unwrap()),
+            Some("Ra8#".to_string())
+        );
+        assert_eq!(
+            game.san(game.parse_san("0-0-0").unwrap()),
+            Some("O-O-O".to_string())
+        );
+    }
+
+    #[test]
+    pub fn san_should_reject_moves_outside_of_the_board() {
+        let game = Game::start_pos();
+        assert_eq!(
+            game.san(Move::Quiet {
+                from: (8, 7),
+                to: (5, 5)
+            }),
+            None
+        );
+        assert_eq!(
+            game.san(Move::Quiet {
+                from: (6, 7),
+                to: (5, 9)
+            }),
+            None
+        );
+    }
+
+    #[test]
+    pub fn parse_san_should_be_lenient() {
+        let game = Game::from_fen("4k3/P7/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            game.parse_san("a8Q").unwrap(),
+            game.parse_san("a8=Q+").unwrap()
+        );
+        assert_eq!(
+            game.parse_san("a7a8q").unwrap(),
+            game.parse_san("a8=Q").unwrap()
+        );
+        assert!(game.parse_san("a8").is_err());
+    }
+
+    #[test]
+    pub fn parse_san_should_fail() {
+        let game = Game::start_pos();
+        assert!(matches!(game.parse_san(""), Err(ParseSanError::Empty)));
+        assert!(matches!(
+            game.parse_san("e5"),
+            Err(ParseSanError::IllegalMove)
+        ));
+        assert!(matches!(
+            game.parse_san("Zz9"),
+            Err(ParseSanError::InvalidSyntax)
+        ));
+
+        let game = Game::from_fen("4k3/8/8/8/8/8/4K3/R6R w - - 0 1").unwrap();
+        assert!(matches!(
+            game.parse_san("Rd1"),
+            Err(ParseSanError::AmbiguousMove)
+        ));
+    }
+}
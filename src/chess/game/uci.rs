@@ -0,0 +1,139 @@
+use crate::{chess::notation::parse_square, error::ParseUciError, Game, Move, PieceType};
+
+impl Game {
+    /// Parses a move in UCI long algebraic notation into the matching legal move
+    ///
+    /// # Arguments
+    /// * `uci` - The move in UCI notation, e.g. `"e2e4"`, `"e1g1"` or `"a7a8n"`
+    ///
+    /// # Returns
+    /// * `Result<Move, ParseUciError>` - The legal move that the string describes, or an error if
+    ///   the string couldn't be parsed or isn't a legal move
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Game, Move};
+    ///
+    /// let game = Game::start_pos();
+    /// let mv = game.parse_uci("g1f3").unwrap();
+    /// assert_eq!(mv, Move::Quiet { from: (6, 7), to: (5, 5) });
+    /// ```
+    pub fn parse_uci(&self, uci: &str) -> Result<Move, ParseUciError> {
+        let uci = uci.trim();
+
+        if !uci.is_ascii() || !(4..=5).contains(&uci.len()) {
+            return Err(ParseUciError::InvalidSyntax);
+        }
+
+        let from = parse_square(&uci[0..2]).ok_or(ParseUciError::InvalidSyntax)?;
+        let to = parse_square(&uci[2..4]).ok_or(ParseUciError::InvalidSyntax)?;
+
+        let promotion = match uci[4..].chars().next() {
+            None => None,
+            Some('n') => Some(PieceType::Knight),
+            Some('b') => Some(PieceType::Bishop),
+            Some('r') => Some(PieceType::Rook),
+            Some('q') => Some(PieceType::Queen),
+            Some(_) => return Err(ParseUciError::InvalidSyntax),
+        };
+
+        self.get_move(from, to)
+            .unwrap_or_default()
+            .into_iter()
+            .find(|mv| mv.promotion() == promotion)
+            .ok_or(ParseUciError::IllegalMove)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn uci_should_round_trip() {
+        let fens_to_test = vec![
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b kq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 1",
+        ];
+
+        for fen in fens_to_test {
+            let game = Game::from_fen(fen).unwrap();
+
+            for mv in game.gen_all_moves().unwrap() {
+                assert_eq!(game.parse_uci(&mv.uci().unwrap()).unwrap(), mv);
+            }
+        }
+    }
+
+    #[test]
+    pub fn uci_should_reject_moves_outside_of_the_board() {
+        assert_eq!(
+            Move::Quiet {
+                from: (0, 9),
+                to: (0, 0)
+            }
+            .uci(),
+            None
+        );
+        assert_eq!(
+            Move::Quiet {
+                from: (0, 0),
+                to: (8, 0)
+            }
+            .uci(),
+            None
+        );
+    }
+
+    #[test]
+    pub fn uci_should_handle_castling_and_promotion() {
+        let game = Game::from_fen("r3k2r/1P6/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+
+        assert!(game.parse_uci("e1g1").unwrap().is_king_side_castle());
+        assert!(game.parse_uci("e1c1").unwrap().is_queen_side_castle());
+        assert_eq!(
+            game.parse_uci("b7a8n").unwrap().promotion(),
+            Some(PieceType::Knight)
+        );
+        assert_eq!(
+            game.parse_uci("b7b8q").unwrap().uci(),
+            Some("b7b8q".to_string())
+        );
+        assert!(matches!(
+            game.parse_uci("b7b8"),
+            Err(ParseUciError::IllegalMove)
+        ));
+    }
+
+    #[test]
+    pub fn parse_uci_should_fail() {
+        let game = Game::start_pos();
+
+        assert!(matches!(
+            game.parse_uci("e2e5"),
+            Err(ParseUciError::IllegalMove)
+        ));
+        assert!(matches!(
+            game.parse_uci("e7e5"),
+            Err(ParseUciError::IllegalMove)
+        ));
+        assert!(matches!(
+            game.parse_uci("e2"),
+            Err(ParseUciError::InvalidSyntax)
+        ));
+        assert!(matches!(
+            game.parse_uci("e2e4x"),
+            Err(ParseUciError::InvalidSyntax)
+        ));
+        assert!(matches!(
+            game.parse_uci("i2i4"),
+            Err(ParseUciError::InvalidSyntax)
+        ));
+        assert!(matches!(
+            game.parse_uci("0000"),
+            Err(ParseUciError::InvalidSyntax)
+        ));
+    }
+}
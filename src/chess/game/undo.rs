@@ -0,0 +1,197 @@
+use crate::{error::GameApplyMoveError, Game, HistoryEntry, Move, Piece, PieceType};
+
+/// The state a move destroys, returned by `Game::make_move` so the move can be taken back
+///
+/// An `UndoInfo` only makes sense for the move and position it was made in, it has to be given
+/// back to `Game::unmake_move` together with the same move.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct UndoInfo {
+    /// The piece that was captured by the move
+    captured: Option<Piece>,
+    /// The castling rights before the move, in the order white kingside, white queenside, black
+    /// kingside and black queenside
+    castling: [bool; 4],
+    /// The pawn that could be captured en passant before the move
+    en_passant: Option<(usize, usize)>,
+    /// The halfmove clock before the move
+    halfmove_clock: u32,
+    /// The fullmove number before the move
+    fullmove_number: u32,
+    /// The hash of the position before the move
+    hash: u64,
+    /// The board before the move, only kept to check that unmaking restores it
+    #[cfg(debug_assertions)]
+    board: crate::Board,
+}
+
+impl UndoInfo {
+    /// Returns the piece that was captured by the move, if any
+    pub fn get_captured(&self) -> Option<Piece> {
+        self.captured
+    }
+}
+
+impl Game {
+    /// Makes a move and returns what is needed to unmake it
+    ///
+    /// This is the fast way to walk a game tree, instead of copying the game for every child the
+    /// same game is changed with `make_move` and changed back with `unmake_move`.
+    ///
+    /// # Arguments
+    /// * `mv` - The move to make, it has to be a legal move for the current position
+    ///
+    /// # Returns
+    /// * `UndoInfo` - The state the move destroyed, to give to `unmake_move`
+    ///
+    /// # Panics
+    /// If there is no piece on the from square of the move
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::Game;
+    ///
+    /// let mut game = Game::start_pos();
+    /// let mv = game.parse_san("e4").unwrap();
+    ///
+    /// let undo = game.make_move(mv);
+    /// assert_ne!(game, Game::start_pos());
+    ///
+    /// game.unmake_move(mv, undo);
+    /// assert_eq!(game, Game::start_pos());
+    /// ```
+    pub fn make_move(&mut self, mv: Move) -> UndoInfo {
+        let undo = UndoInfo {
+            captured: mv.capture().and_then(|(x, y)| self.board.get_tile(x, y)),
+            castling: [
+                self.white_kingside_castle,
+                self.white_queenside_castle,
+                self.black_kingside_castle,
+                self.black_queenside_castle,
+            ],
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+            fullmove_number: self.fullmove_number,
+            hash: self.hash,
+            #[cfg(debug_assertions)]
+            board: self.board,
+        };
+
+        self.apply_move_unchecked(mv);
+
+        undo
+    }
+
+    /// Applies a move and returns the state that the move destroyed, so that it can be taken back
+    /// with `undo_move_entry`
+    pub(crate) fn apply_move_recorded(
+        &mut self,
+        mv: Move,
+    ) -> Result<HistoryEntry, GameApplyMoveError> {
+        self.check_move(mv)?;
+
+        Ok(HistoryEntry {
+            mv,
+            undo: self.make_move(mv),
+        })
+    }
+
+    /// Takes back the move of a history entry, the move has to be the last move that was applied
+    pub(crate) fn undo_move_entry(&mut self, entry: &HistoryEntry) {
+        self.unmake_move(entry.mv, entry.undo);
+    }
+
+    /// Takes back a move made with `make_move`
+    ///
+    /// The position is restored exactly, including the castling rights, the en passant pawn, the
+    /// clocks and the hash.
+    ///
+    /// # Arguments
+    /// * `mv` - The move to take back, it has to be the last move that was made
+    /// * `undo` - The `UndoInfo` that `make_move` returned for the move
+    pub fn unmake_move(&mut self, mv: Move, undo: UndoInfo) {
+        let (from_x, from_y) = mv.from();
+        let (to_x, to_y) = mv.to();
+
+        let piece = self
+            .board
+            .get_tile(to_x, to_y)
+            .expect("The moved piece is on the to square");
+
+        let piece = if mv.is_promotion() {
+            Piece {
+                piece_type: PieceType::Pawn,
+                color: piece.color,
+            }
+        } else {
+            piece
+        };
+
+        self.board.remove_tile(to_x, to_y);
+        self.board.set_tile(from_x, from_y, piece);
+
+        if let (Some(rook_from), Some(rook_to)) = (mv.rook_from(), mv.rook_to()) {
+            if let Some(rook) = self.board.get_tile(rook_to.0, rook_to.1) {
+                self.board.remove_tile(rook_to.0, rook_to.1);
+                self.board.set_tile(rook_from.0, rook_from.1, rook);
+            }
+        }
+
+        if let (Some((c_x, c_y)), Some(captured)) = (mv.capture(), undo.captured) {
+            self.board.set_tile(c_x, c_y, captured);
+        }
+
+        self.white_kingside_castle = undo.castling[0];
+        self.white_queenside_castle = undo.castling[1];
+        self.black_kingside_castle = undo.castling[2];
+        self.black_queenside_castle = undo.castling[3];
+
+        self.en_passant = undo.en_passant;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.fullmove_number = undo.fullmove_number;
+        self.hash = undo.hash;
+
+        self.turn = self.turn.opposite();
+
+        #[cfg(debug_assertions)]
+        {
+            debug_assert_eq!(
+                self.board, undo.board,
+                "unmake_move didn't restore the board"
+            );
+            debug_assert_eq!(
+                self.hash,
+                self.compute_hash(),
+                "unmake_move didn't restore the hash"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn unmake_should_restore_the_game() {
+        let fens_to_test = vec![
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "4k3/8/8/8/8/8/8/4K2R w - - 4294967295 1",
+            "4k3/8/8/8/8/8/8/4K2R b - - 0 4294967295",
+        ];
+
+        for fen in fens_to_test {
+            let mut game = Game::from_fen(fen).unwrap();
+            let before = game;
+
+            for mv in before.gen_all_moves().unwrap() {
+                let undo = game.make_move(mv);
+                game.unmake_move(mv, undo);
+
+                assert_eq!(game, before, "{} {:?}", fen, mv);
+            }
+        }
+    }
+}
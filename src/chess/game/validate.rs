@@ -0,0 +1,237 @@
+use crate::{
+    error::{FromFenStrictError, PositionError},
+    Bitboard, Color, Game, Piece, PieceType, Square,
+};
+
+/// The first and eighth rank, where pawns can never be
+const BACK_RANKS: Bitboard = Bitboard(0xff00_0000_0000_00ff);
+
+impl Game {
+    /// Creates a new game from a FEN string and rejects positions that can't happen in a game
+    ///
+    /// The same as `from_fen`, but the position also has to pass `validate`.
+    ///
+    /// # Arguments
+    /// * `fen` - A string that holds the FEN string
+    ///
+    /// # Returns
+    /// * `Result<game, FromFenStrictError>` - A result that holds the game if the FEN string is
+    ///   valid and the position is legal, or an error that says why it isn't
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{error::{FromFenStrictError, PositionError}, Color, Game};
+    ///
+    /// let error = Game::from_fen_strict("8/8/8/8/8/8/8/4K3 w - - 0 1").unwrap_err();
+    /// assert!(matches!(
+    ///     error,
+    ///     FromFenStrictError::IllegalPosition(errors)
+    ///         if errors == vec![PositionError::MissingKing(Color::Black)]
+    /// ));
+    /// ```
+    pub fn from_fen_strict(fen: &str) -> Result<Game, FromFenStrictError> {
+        let game = Game::from_fen(fen)?;
+
+        let errors = game.validate();
+        if !errors.is_empty() {
+            return Err(FromFenStrictError::IllegalPosition(errors));
+        }
+
+        Ok(game)
+    }
+
+    /// Returns every reason that the position can't happen in a game of chess
+    ///
+    /// `from_fen` and `set_board` accept any board, this checks the kings, the pawns, the amount
+    /// of pieces, checks, castling rights and the en passant pawn.
+    ///
+    /// # Returns
+    /// * `Vec<PositionError>` - All problems with the position, it's empty if the position is
+    ///   legal
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{error::PositionError, Game};
+    ///
+    /// assert!(Game::start_pos().validate().is_empty());
+    ///
+    /// // White is to move but black is in check
+    /// let game = Game::from_fen("4k3/8/8/8/8/8/8/4RK2 w - - 0 1").unwrap();
+    /// assert_eq!(game.validate(), vec![PositionError::OpponentInCheck]);
+    /// ```
+    pub fn validate(&self) -> Vec<PositionError> {
+        let mut errors = vec![];
+
+        for color in [Color::White, Color::Black] {
+            match self.board.pieces(color, PieceType::King).count() {
+                0 => errors.push(PositionError::MissingKing(color)),
+                1 => (),
+                _ => errors.push(PositionError::TooManyKings(color)),
+            }
+
+            if self.board.pieces(color, PieceType::Pawn).count() > 8 {
+                errors.push(PositionError::TooManyPawns(color));
+            }
+
+            if self.board.color_pieces(color).count() > 16 {
+                errors.push(PositionError::TooManyPieces(color));
+            }
+        }
+
+        for pos in (self.board.type_pieces(PieceType::Pawn) & BACK_RANKS).squares() {
+            let square = Square::try_from(pos).expect("Bitboard squares are on the board");
+            errors.push(PositionError::PawnOnBackRank(square));
+        }
+
+        if self.can_capture_king(self.turn) {
+            errors.push(PositionError::OpponentInCheck);
+        }
+
+        if let Some(king) = self.board.get_king_pos(self.turn) {
+            if self.board.attackers(king, self.turn.opposite()).count() > 2 {
+                errors.push(PositionError::TooManyCheckers);
+            }
+        }
+
+        errors.extend(self.castling_errors());
+
+        if !self.is_valid_en_passant() {
+            errors.push(PositionError::InvalidEnPassant);
+        }
+
+        errors
+    }
+
+    /// Returns an error for every castling right without the king and rook on their starting
+    /// squares
+    fn castling_errors(&self) -> Vec<PositionError> {
+        let rights = [
+            (Color::White, true, self.white_kingside_castle, 7),
+            (Color::White, false, self.white_queenside_castle, 7),
+            (Color::Black, true, self.black_kingside_castle, 0),
+            (Color::Black, false, self.black_queenside_castle, 0),
+        ];
+
+        rights
+            .into_iter()
+            .filter(|(color, kingside, right, y)| {
+                let rook_x = if *kingside { 7 } else { 0 };
+                let has_piece = |x, piece_type| {
+                    self.board.get_tile(x, *y)
+                        == Some(Piece {
+                            piece_type,
+                            color: *color,
+                        })
+                };
+
+                *right && !(has_piece(4, PieceType::King) && has_piece(rook_x, PieceType::Rook))
+            })
+            .map(|(color, kingside, ..)| PositionError::InvalidCastlingRights { color, kingside })
+            .collect()
+    }
+
+    /// Returns if the en passant pawn can have moved two squares with the last move
+    ///
+    /// The square it passed over and the square it came from have to be empty.
+    fn is_valid_en_passant(&self) -> bool {
+        let Some((ep_x, ep_y)) = self.en_passant else {
+            return true;
+        };
+
+        let (behind, start) = match self.turn {
+            Color::White => (ep_y - 1, ep_y - 2),
+            Color::Black => (ep_y + 1, ep_y + 2),
+        };
+
+        self.board.get_tile(ep_x, behind).is_none() && self.board.get_tile(ep_x, start).is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn should_accept_legal_positions() {
+        let fens_to_test = vec![
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "8/8/8/2k5/3Pp3/8/8/4K3 b - d3 0 1",
+        ];
+
+        for fen in fens_to_test {
+            assert_eq!(Game::from_fen(fen).unwrap().validate(), vec![], "{}", fen);
+            assert!(Game::from_fen_strict(fen).is_ok());
+        }
+    }
+
+    #[test]
+    pub fn should_find_every_problem() {
+        let errors_to_test = vec![
+            (
+                "4k3/8/8/8/8/8/8/8 w - - 0 1",
+                vec![PositionError::MissingKing(Color::White)],
+            ),
+            (
+                "4k3/8/8/8/8/8/8/K3K3 w - - 0 1",
+                vec![PositionError::TooManyKings(Color::White)],
+            ),
+            (
+                "P3k3/8/8/8/8/8/8/4K2p w - - 0 1",
+                vec![
+                    PositionError::PawnOnBackRank("a8".parse().unwrap()),
+                    PositionError::PawnOnBackRank("h1".parse().unwrap()),
+                ],
+            ),
+            (
+                "4k3/pppppppp/p7/8/8/8/8/4K3 w - - 0 1",
+                vec![PositionError::TooManyPawns(Color::Black)],
+            ),
+            ("4k3/8/8/8/8/8/8/4K2R b K - 0 1", vec![]),
+            (
+                "4k3/8/8/8/8/8/8/R3K3 w KQ - 0 1",
+                vec![PositionError::InvalidCastlingRights {
+                    color: Color::White,
+                    kingside: true,
+                }],
+            ),
+            ("r3k3/8/8/8/8/8/8/4K3 w q - 0 1", vec![]),
+            (
+                "r2k4/8/8/8/8/8/8/4K3 w q - 0 1",
+                vec![PositionError::InvalidCastlingRights {
+                    color: Color::Black,
+                    kingside: false,
+                }],
+            ),
+            (
+                "4k3/4p3/8/4p3/8/8/8/4K3 w - e6 0 1",
+                vec![PositionError::InvalidEnPassant],
+            ),
+            (
+                "4k3/8/8/8/3P4/8/3P4/4K3 b - d3 0 1",
+                vec![PositionError::InvalidEnPassant],
+            ),
+            (
+                "4k3/8/8/8/1b6/8/2n5/r3K3 w - - 0 1",
+                vec![PositionError::TooManyCheckers],
+            ),
+        ];
+
+        for (fen, errors) in errors_to_test {
+            assert_eq!(Game::from_fen(fen).unwrap().validate(), errors, "{}", fen);
+        }
+    }
+
+    #[test]
+    pub fn from_fen_strict_should_reject_illegal_positions() {
+        assert!(matches!(
+            Game::from_fen_strict("4k3/8/8/8/8/8/8/4RK2 w - - 0 1"),
+            Err(FromFenStrictError::IllegalPosition(_))
+        ));
+        assert!(matches!(
+            Game::from_fen_strict("4k3/8/8/8/8/8/8/4KR2 b - -"),
+            Err(FromFenStrictError::InvalidFen(_))
+        ));
+    }
+}
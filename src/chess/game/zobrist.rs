@@ -0,0 +1,253 @@
+//! Zobrist hashing of positions
+//!
+//! Every piece on every square, the turn, every castling right and every en passant file has a
+//! random 64 bit key. The hash of a position is all keys that apply xor'ed together, which means
+//! that a move can update the hash by only xor'ing the keys that changed.
+
+use crate::{Color, Game, Piece, PieceType};
+
+/// All random keys that make up a hash
+struct Keys {
+    pieces: [[[u64; 64]; 6]; 2],
+    black_to_move: u64,
+    castling: [u64; 4],
+    en_passant: [u64; 8],
+}
+
+/// The keys are generated at compile time with splitmix64, so they are the same in every build
+const KEYS: Keys = {
+    let mut state = 0x2545_f491_4f6c_dd1d;
+
+    const fn next(state: &mut u64) -> u64 {
+        *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    let mut keys = Keys {
+        pieces: [[[0; 64]; 6]; 2],
+        black_to_move: 0,
+        castling: [0; 4],
+        en_passant: [0; 8],
+    };
+
+    let mut color = 0;
+    while color < 2 {
+        let mut piece_type = 0;
+        while piece_type < 6 {
+            let mut square = 0;
+            while square < 64 {
+                keys.pieces[color][piece_type][square] = next(&mut state);
+                square += 1;
+            }
+            piece_type += 1;
+        }
+        color += 1;
+    }
+
+    keys.black_to_move = next(&mut state);
+
+    let mut i = 0;
+    while i < 4 {
+        keys.castling[i] = next(&mut state);
+        i += 1;
+    }
+
+    let mut i = 0;
+    while i < 8 {
+        keys.en_passant[i] = next(&mut state);
+        i += 1;
+    }
+
+    keys
+};
+
+/// Returns the key of a piece on a square
+fn piece_key(piece: Piece, x: usize, y: usize) -> u64 {
+    KEYS.pieces[piece.color as usize][piece.piece_type as usize][y * 8 + x]
+}
+
+impl Game {
+    /// Returns the Zobrist hash of the position
+    ///
+    /// The hash covers the pieces, the turn, the castling rights and the en passant file. The en
+    /// passant file is only included if a pawn stands next to the pawn that can be captured, so
+    /// positions that only differ by an en passant square nobody can use get the same hash.
+    ///
+    /// Two games with the same position always have the same hash, two games with different
+    /// positions almost always have different hashes.
+    ///
+    /// # Returns
+    /// * `u64` - The hash of the position
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::Game;
+    ///
+    /// let mut a = Game::start_pos();
+    /// for san in ["Nf3", "Nf6", "Nc3"] {
+    ///     a.apply_move(a.parse_san(san).unwrap()).unwrap();
+    /// }
+    ///
+    /// let mut b = Game::start_pos();
+    /// for san in ["Nc3", "Nf6", "Nf3"] {
+    ///     b.apply_move(b.parse_san(san).unwrap()).unwrap();
+    /// }
+    ///
+    /// assert_eq!(a.hash_key(), b.hash_key());
+    /// ```
+    pub fn hash_key(&self) -> u64 {
+        self.hash
+    }
+
+    /// Returns the hash of the position without the en passant file
+    pub(crate) fn hash_key_without_en_passant(&self) -> u64 {
+        self.hash ^ self.en_passant_key()
+    }
+
+    /// Calculates the hash of the position from scratch
+    pub(crate) fn compute_hash(&self) -> u64 {
+        let mut hash = 0;
+
+        for (x, y) in self.board.occupied().squares() {
+            let piece = self.board.get_tile(x, y).expect("The square is occupied");
+            hash ^= piece_key(piece, x, y);
+        }
+
+        if self.turn == Color::Black {
+            hash ^= KEYS.black_to_move;
+        }
+
+        hash ^ self.castling_key() ^ self.en_passant_key()
+    }
+
+    /// Sets a tile on the board and updates the hash
+    pub(crate) fn set_tile(&mut self, x: usize, y: usize, piece: Piece) {
+        self.remove_tile(x, y);
+
+        self.board.set_tile(x, y, piece);
+        self.hash ^= piece_key(piece, x, y);
+    }
+
+    /// Removes a tile from the board and updates the hash
+    pub(crate) fn remove_tile(&mut self, x: usize, y: usize) {
+        if let Some(piece) = self.board.get_tile(x, y) {
+            self.board.remove_tile(x, y);
+            self.hash ^= piece_key(piece, x, y);
+        }
+    }
+
+    /// Returns the xor of the keys of all castling rights that are left
+    pub(crate) fn castling_key(&self) -> u64 {
+        [
+            self.white_kingside_castle,
+            self.white_queenside_castle,
+            self.black_kingside_castle,
+            self.black_queenside_castle,
+        ]
+        .into_iter()
+        .zip(KEYS.castling)
+        .filter(|(right, _)| *right)
+        .fold(0, |hash, (_, key)| hash ^ key)
+    }
+
+    /// Returns the key of the en passant file, or 0 if no pawn of the current turn stands next to
+    /// the pawn that can be captured
+    pub(crate) fn en_passant_key(&self) -> u64 {
+        let Some((ep_x, ep_y)) = self.en_passant else {
+            return 0;
+        };
+
+        let capturer = Piece {
+            piece_type: PieceType::Pawn,
+            color: self.turn,
+        };
+
+        let can_capture = [ep_x.checked_sub(1), Some(ep_x + 1).filter(|x| *x < 8)]
+            .into_iter()
+            .flatten()
+            .any(|x| self.board.get_tile(x, ep_y) == Some(capturer));
+
+        if can_capture {
+            KEYS.en_passant[ep_x]
+        } else {
+            0
+        }
+    }
+
+    /// Flips the turn and updates the hash
+    pub(crate) fn flip_turn(&mut self) {
+        self.turn = self.turn.opposite();
+        self.hash ^= KEYS.black_to_move;
+    }
+}
+
+impl std::hash::Hash for Game {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn hash_should_be_updated_incrementally() {
+        let fens_to_test = vec![
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        ];
+
+        for fen in fens_to_test {
+            let game = Game::from_fen(fen).unwrap();
+            assert_eq!(game.hash_key(), game.compute_hash());
+
+            for mv in game.gen_all_moves().unwrap() {
+                let mut child = game;
+                child.apply_move(mv).unwrap();
+                assert_eq!(child.hash_key(), child.compute_hash(), "{} {:?}", fen, mv);
+
+                for mv in child.gen_all_moves().unwrap_or_default() {
+                    let mut grandchild = child;
+                    grandchild.apply_move(mv).unwrap();
+                    assert_eq!(grandchild.hash_key(), grandchild.compute_hash());
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn hash_should_depend_on_position() {
+        let hashes = [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR b KQkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w Kkq - 0 1",
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w - - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq - 0 3",
+        ]
+        .map(|fen| Game::from_fen(fen).unwrap().hash_key());
+
+        for (i, a) in hashes.iter().enumerate() {
+            for b in &hashes[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+
+        // Clocks are not part of the position, and neither is an en passant square that can't
+        // be used
+        assert_eq!(
+            Game::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1")
+                .unwrap()
+                .hash_key(),
+            Game::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 5 9")
+                .unwrap()
+                .hash_key()
+        );
+    }
+}
@@ -0,0 +1,486 @@
+use crate::{error::GameApplyMoveError, Color, Game, Move, Outcome, Termination, UndoInfo};
+
+/// A move that has been played together with the state it destroyed
+///
+/// This is everything that is needed to take the move back.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// The move that was played
+    pub mv: Move,
+    /// The state the move destroyed
+    pub undo: UndoInfo,
+}
+
+/// A game of chess that remembers how it got to its current position
+///
+/// `Game` is only a snapshot of a position, `GameHistory` wraps it and records every move that is
+/// applied, which makes it possible to take moves back and step through the game.
+///
+/// # Examples
+/// ```
+/// use fritiofr_chess::{Game, GameHistory};
+///
+/// let mut history = GameHistory::new(Game::start_pos());
+///
+/// let mv = history.get_game().parse_san("e4").unwrap();
+/// history.apply_move(mv).unwrap();
+///
+/// assert_eq!(history.undo_move(), Some(mv));
+/// assert_eq!(history.get_game(), Game::start_pos());
+///
+/// assert_eq!(history.redo_move(), Some(mv));
+/// assert_eq!(history.moves().collect::<Vec<_>>(), vec![mv]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameHistory {
+    start: Game,
+    game: Game,
+    entries: Vec<HistoryEntry>,
+    /// Moves that have been taken back, the last one is the next one to redo
+    undone: Vec<Move>,
+    /// An ending that wasn't decided on the board, like a resignation or a claimed draw
+    ending: Option<Outcome>,
+}
+
+impl GameHistory {
+    /// Creates a new history that starts from a position
+    ///
+    /// # Arguments
+    /// * `start` - The position the game starts from
+    pub fn new(start: Game) -> GameHistory {
+        GameHistory {
+            start,
+            game: start,
+            entries: vec![],
+            undone: vec![],
+            ending: None,
+        }
+    }
+
+    /// Returns the current position
+    ///
+    /// # Return
+    /// * `Game` - The game after all moves in the history have been played
+    pub fn get_game(&self) -> Game {
+        self.game
+    }
+
+    /// Returns the position the history started from
+    ///
+    /// # Return
+    /// * `Game` - The game before any moves were played
+    pub fn get_start(&self) -> Game {
+        self.start
+    }
+
+    /// Applies a move to the current position and records it
+    ///
+    /// Applying a move forgets all moves that have been taken back, so they can't be redone. No
+    /// moves can be applied after the game has ended by resignation, agreement, timeout or a
+    /// claimed draw.
+    ///
+    /// # Arguments
+    /// * `mv` - The move to apply, it has to be a legal move for the current position
+    ///
+    /// # Returns
+    /// * `Result<(), GameApplyMoveError>` - A result that holds nothing if the move was applied or
+    ///   an error if the move isn't legal or the game has ended
+    pub fn apply_move(&mut self, mv: Move) -> Result<(), GameApplyMoveError> {
+        if self.ending.is_some() {
+            return Err(GameApplyMoveError::GameOver);
+        }
+
+        self.push_move(mv)?;
+        self.undone.clear();
+
+        Ok(())
+    }
+
+    /// Takes back the last move
+    ///
+    /// If the game has ended by resignation, agreement, timeout or a claimed draw, that ending is
+    /// forgotten.
+    ///
+    /// # Returns
+    /// * `Option<Move>` - The move that was taken back, if there are no moves this will return
+    ///   None
+    pub fn undo_move(&mut self) -> Option<Move> {
+        let entry = self.entries.pop()?;
+
+        self.ending = None;
+        self.game.undo_move_entry(&entry);
+        self.undone.push(entry.mv);
+
+        Some(entry.mv)
+    }
+
+    /// Plays the last move that was taken back again
+    ///
+    /// # Returns
+    /// * `Option<Move>` - The move that was played again, if no move has been taken back or the
+    ///   game has ended this will return None
+    pub fn redo_move(&mut self) -> Option<Move> {
+        if self.ending.is_some() {
+            return None;
+        }
+
+        let mv = self.undone.pop()?;
+
+        self.push_move(mv)
+            .expect("Undone moves were legal in this position");
+
+        Some(mv)
+    }
+
+    /// Returns all moves that have been played together with the state they destroyed
+    ///
+    /// # Returns
+    /// * `&[HistoryEntry]` - The entries, the first one is the first move of the game
+    pub fn history(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Returns an iterator over all moves that have been played, in order
+    pub fn moves(&self) -> impl Iterator<Item = Move> + '_ {
+        self.entries.iter().map(|entry| entry.mv)
+    }
+
+    /// Returns how many times the current position has occurred in the game, including now
+    ///
+    /// Only positions since the last capture or pawn move are checked, since no position before
+    /// that can come back.
+    pub fn repetitions(&self) -> usize {
+        let mut game = self.game;
+        let mut count = 1;
+
+        for entry in self
+            .entries
+            .iter()
+            .rev()
+            .take(self.game.get_halfmove_clock() as usize)
+        {
+            game.undo_move_entry(entry);
+
+            if game.hash_key_without_en_passant() == self.game.hash_key_without_en_passant()
+                && game.is_same_position(&self.game)
+            {
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Returns if the current position has occurred at least three times
+    ///
+    /// This is a draw that a player can claim, the game doesn't end by itself.
+    pub fn is_threefold_repetition(&self) -> bool {
+        self.repetitions() >= 3
+    }
+
+    /// Returns if the current position has occurred at least five times
+    ///
+    /// The game ends in a draw automatically when this happens.
+    pub fn is_fivefold_repetition(&self) -> bool {
+        self.repetitions() >= 5
+    }
+
+    /// Returns how the game ended, if it has ended
+    ///
+    /// This is everything `Game::outcome` checks, plus the fivefold repetition rule and endings
+    /// that have been recorded with `resign`, `agree_draw`, `flag` or `claim_draw`.
+    ///
+    /// # Returns
+    /// * `Option<Outcome>` - The outcome of the game, or None if the game is still going
+    pub fn outcome(&self) -> Option<Outcome> {
+        if self.ending.is_some() {
+            return self.ending;
+        }
+
+        let outcome = self.game.outcome();
+
+        // Checkmate and stalemate end the game on the move, so they always come first
+        if outcome.is_none() && self.is_fivefold_repetition() {
+            return Some(Outcome::draw(Termination::FivefoldRepetition));
+        }
+
+        outcome
+    }
+
+    /// Ends the game with a resignation
+    ///
+    /// # Arguments
+    /// * `color` - The color that resigns
+    pub fn resign(&mut self, color: Color) {
+        self.ending = Some(Outcome::win(color.opposite(), Termination::Resignation));
+    }
+
+    /// Ends the game with a draw that the players agreed to
+    pub fn agree_draw(&mut self) {
+        self.ending = Some(Outcome::draw(Termination::Agreement));
+    }
+
+    /// Ends the game because a player ran out of time
+    ///
+    /// The player that ran out of time loses, unless the other player can't checkmate in which
+    /// case the game is drawn.
+    ///
+    /// # Arguments
+    /// * `color` - The color that ran out of time
+    pub fn flag(&mut self, color: Color) {
+        self.ending = Some(if self.game.has_insufficient_material(color.opposite()) {
+            Outcome::draw(Termination::Timeout)
+        } else {
+            Outcome::win(color.opposite(), Termination::Timeout)
+        });
+    }
+
+    /// Claims a draw by threefold repetition or the fifty-move rule
+    ///
+    /// # Returns
+    /// * `Option<Outcome>` - The drawn outcome if the claim was valid, if it wasn't the game goes
+    ///   on and this will return None
+    pub fn claim_draw(&mut self) -> Option<Outcome> {
+        let reason = if self.is_threefold_repetition() {
+            Termination::ThreefoldRepetition
+        } else if self.game.can_claim_fifty_move_draw() {
+            Termination::FiftyMoves
+        } else {
+            return None;
+        };
+
+        self.ending = Some(Outcome::draw(reason));
+        self.ending
+    }
+
+    fn push_move(&mut self, mv: Move) -> Result<(), GameApplyMoveError> {
+        let entry = self.game.apply_move_recorded(mv)?;
+        self.entries.push(entry);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays all moves, then undoes them one by one and checks that every position is restored
+    fn assert_undo_restores(fen: &str, sans: &[&str]) {
+        let mut history = GameHistory::new(Game::from_fen(fen).unwrap());
+        let mut positions = vec![history.get_game()];
+
+        for san in sans {
+            let mv = history.get_game().parse_san(san).unwrap();
+            history.apply_move(mv).unwrap();
+            positions.push(history.get_game());
+        }
+
+        while let Some(expected) = positions.pop() {
+            assert_eq!(history.get_game(), expected);
+            history.undo_move();
+        }
+
+        assert!(history.history().is_empty());
+    }
+
+    #[test]
+    pub fn undo_should_restore_positions() {
+        assert_undo_restores(
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            &[
+                "e4", "d5", "exd5", "c5", "dxc6", "Qd6", "cxb7", "Qxh2", "bxa8=N", "Qxh1",
+            ],
+        );
+        assert_undo_restores(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            &["O-O-O", "O-O", "a4", "bxa3", "Bxa6", "Qd8"],
+        );
+    }
+
+    #[test]
+    pub fn redo_should_replay_undone_moves() {
+        let mut history = GameHistory::new(Game::start_pos());
+
+        for san in ["e4", "e5", "Nf3"] {
+            let mv = history.get_game().parse_san(san).unwrap();
+            history.apply_move(mv).unwrap();
+        }
+        let end = history.get_game();
+
+        history.undo_move();
+        history.undo_move();
+        assert_eq!(history.moves().count(), 1);
+
+        history.redo_move();
+        history.redo_move();
+        assert_eq!(history.get_game(), end);
+        assert_eq!(history.redo_move(), None);
+
+        history.undo_move();
+        let mv = history.get_game().parse_san("Nc3").unwrap();
+        history.apply_move(mv).unwrap();
+        assert_eq!(history.redo_move(), None);
+        assert_eq!(history.moves().last(), Some(mv));
+    }
+
+    #[test]
+    pub fn should_reject_illegal_moves() {
+        let mut history = GameHistory::new(Game::start_pos());
+
+        assert!(history
+            .apply_move(Move::Quiet {
+                from: (4, 6),
+                to: (4, 3)
+            })
+            .is_err());
+        assert_eq!(history.undo_move(), None);
+        assert_eq!(history.get_game(), Game::start_pos());
+    }
+
+    /// Plays a list of moves in SAN
+    fn play(history: &mut GameHistory, sans: &[&str]) {
+        for san in sans {
+            let mv = history.get_game().parse_san(san).unwrap();
+            history.apply_move(mv).unwrap();
+        }
+    }
+
+    #[test]
+    pub fn should_count_repetitions() {
+        let mut history = GameHistory::new(Game::start_pos());
+        let shuffle = ["Nf3", "Nf6", "Ng1", "Ng8"];
+
+        assert_eq!(history.repetitions(), 1);
+
+        play(&mut history, &shuffle);
+        assert_eq!(history.repetitions(), 2);
+        assert!(!history.is_threefold_repetition());
+
+        play(&mut history, &shuffle[..2]);
+        assert_eq!(history.repetitions(), 2);
+
+        play(&mut history, &shuffle[2..]);
+        assert!(history.is_threefold_repetition());
+        assert!(!history.is_fivefold_repetition());
+
+        play(&mut history, &shuffle);
+        play(&mut history, &shuffle);
+        assert_eq!(history.repetitions(), 5);
+        assert!(history.is_fivefold_repetition());
+
+        // A pawn move makes all earlier positions impossible to reach again
+        play(&mut history, &["e4"]);
+        assert_eq!(history.repetitions(), 1);
+    }
+
+    #[test]
+    pub fn repetitions_should_only_care_about_capturable_en_passant() {
+        // After 1. e4 black can't capture en passant, so the position is the same as when the
+        // knights have moved back
+        let mut history = GameHistory::new(Game::start_pos());
+        play(&mut history, &["e4", "Nf6", "Nf3", "Ng8", "Ng1"]);
+        assert_eq!(history.repetitions(), 2);
+
+        // Here white can capture en passant after d5, so the first position doesn't count
+        let mut history = GameHistory::new(
+            Game::from_fen("rnbqkbnr/pppppppp/8/4P3/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap(),
+        );
+        play(&mut history, &["d5", "Nf3", "Nf6", "Ng1", "Ng8"]);
+        assert_eq!(history.repetitions(), 1);
+        play(&mut history, &["Nf3", "Nf6", "Ng1", "Ng8"]);
+        assert_eq!(history.repetitions(), 2);
+    }
+
+    #[test]
+    pub fn should_end_by_repetition() {
+        let mut history = GameHistory::new(Game::start_pos());
+        let shuffle = ["Nf3", "Nf6", "Ng1", "Ng8"];
+
+        assert_eq!(history.claim_draw(), None);
+
+        play(&mut history, &shuffle);
+        play(&mut history, &shuffle);
+        assert_eq!(history.outcome(), None);
+        assert_eq!(
+            history.claim_draw(),
+            Some(Outcome::draw(Termination::ThreefoldRepetition))
+        );
+        assert_eq!(history.outcome(), history.claim_draw());
+
+        history.undo_move();
+        assert_eq!(history.outcome(), None);
+        history.redo_move();
+
+        play(&mut history, &shuffle);
+        play(&mut history, &shuffle);
+        assert_eq!(
+            history.outcome(),
+            Some(Outcome::draw(Termination::FivefoldRepetition))
+        );
+    }
+
+    #[test]
+    pub fn should_end_by_events() {
+        let mut history = GameHistory::new(Game::start_pos());
+        history.resign(Color::White);
+        assert_eq!(
+            history.outcome(),
+            Some(Outcome::win(Color::Black, Termination::Resignation))
+        );
+
+        history.agree_draw();
+        assert_eq!(
+            history.outcome(),
+            Some(Outcome::draw(Termination::Agreement))
+        );
+
+        history.flag(Color::Black);
+        assert_eq!(
+            history.outcome(),
+            Some(Outcome::win(Color::White, Termination::Timeout))
+        );
+
+        // Black can't lose on time when white only has a knight
+        let mut history =
+            GameHistory::new(Game::from_fen("4k3/8/8/8/8/8/8/3qKN2 w - - 0 1").unwrap());
+        history.flag(Color::Black);
+        assert_eq!(history.outcome(), Some(Outcome::draw(Termination::Timeout)));
+
+        let mut history =
+            GameHistory::new(Game::from_fen("4k3/8/8/8/8/8/8/3qKN2 w - - 100 80").unwrap());
+        assert_eq!(
+            history.claim_draw(),
+            Some(Outcome::draw(Termination::FiftyMoves))
+        );
+    }
+
+    #[test]
+    pub fn should_reject_moves_after_the_game_has_ended() {
+        let mut history = GameHistory::new(Game::start_pos());
+        let e4 = history.get_game().parse_san("e4").unwrap();
+        history.apply_move(e4).unwrap();
+        history.undo_move();
+
+        history.resign(Color::Black);
+        assert_eq!(history.apply_move(e4), Err(GameApplyMoveError::GameOver));
+        assert_eq!(history.redo_move(), None);
+        assert_eq!(history.get_game(), Game::start_pos());
+        assert_eq!(
+            history.outcome(),
+            Some(Outcome::win(Color::White, Termination::Resignation))
+        );
+
+        let mut history = GameHistory::new(Game::start_pos());
+        history.agree_draw();
+        assert_eq!(history.apply_move(e4), Err(GameApplyMoveError::GameOver));
+
+        // Taking back a move forgets the ending, so the game can go on
+        let mut history = GameHistory::new(Game::start_pos());
+        history.apply_move(e4).unwrap();
+        history.flag(Color::White);
+        history.undo_move();
+        assert_eq!(history.outcome(), None);
+        assert_eq!(history.apply_move(e4), Ok(()));
+    }
+}
@@ -7,7 +7,36 @@ pub use game::*;
 mod board;
 pub use board::*;
 
+mod bitboard;
+pub use bitboard::*;
+
+mod square;
+pub use square::*;
+
+mod attacks;
+pub use attacks::attacks_from;
+
 pub mod error;
 
+pub mod pgn;
+
+pub mod epd;
+
+pub mod eval;
+
+mod history;
+pub use history::*;
+
 mod mv;
 pub use mv::*;
+
+mod move_list;
+pub use move_list::*;
+
+mod packed_move;
+pub use packed_move::*;
+
+mod outcome;
+pub use outcome::*;
+
+mod notation;
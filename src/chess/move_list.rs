@@ -0,0 +1,145 @@
+use crate::Move;
+
+/// The most moves a `MoveList` can hold, no chess position has more legal moves than this
+pub const MAX_MOVES: usize = 256;
+
+/// A list of moves with a fixed capacity that lives on the stack
+///
+/// Move generation into a `MoveList` never allocates, which makes it a good fit for search code
+/// that generates moves in every node. The list dereferences to a slice of the moves in it.
+///
+/// # Examples
+/// ```
+/// use fritiofr_chess::{Game, MoveList};
+///
+/// let game = Game::start_pos();
+/// let mut moves = MoveList::new();
+/// game.generate_into(&mut moves);
+///
+/// assert_eq!(moves.len(), 20);
+/// assert!(moves.iter().all(|mv| game.gen_all_moves().unwrap().contains(mv)));
+/// ```
+#[derive(Clone, Copy)]
+pub struct MoveList {
+    moves: [Move; MAX_MOVES],
+    len: usize,
+}
+
+impl MoveList {
+    /// Returns an empty move list
+    pub fn new() -> MoveList {
+        MoveList {
+            // The filler is never read, only the first `len` moves are used
+            moves: [Move::Quiet {
+                from: (0, 0),
+                to: (0, 0),
+            }; MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    /// Adds a move to the end of the list
+    ///
+    /// # Arguments
+    /// * `mv` - The move to add
+    ///
+    /// # Panics
+    /// If the list already holds `MAX_MOVES` moves
+    pub fn push(&mut self, mv: Move) {
+        assert!(
+            self.len < MAX_MOVES,
+            "A MoveList can't hold more than {MAX_MOVES} moves"
+        );
+
+        self.moves[self.len] = mv;
+        self.len += 1;
+    }
+
+    /// Removes all moves from the list
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Keeps only the moves from `start` and onwards that the predicate returns true for, the
+    /// moves before `start` are always kept
+    pub(crate) fn retain_from(&mut self, start: usize, mut f: impl FnMut(&Move) -> bool) {
+        let mut kept = start;
+
+        for i in start..self.len {
+            if f(&self.moves[i]) {
+                self.moves[kept] = self.moves[i];
+                kept += 1;
+            }
+        }
+
+        self.len = kept;
+    }
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        MoveList::new()
+    }
+}
+
+impl std::ops::Deref for MoveList {
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+}
+
+impl std::ops::DerefMut for MoveList {
+    fn deref_mut(&mut self) -> &mut [Move] {
+        &mut self.moves[..self.len]
+    }
+}
+
+impl std::fmt::Debug for MoveList {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a MoveList {
+    type Item = &'a Move;
+    type IntoIter = std::slice::Iter<'a, Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Extend<Move> for MoveList {
+    fn extend<T: IntoIterator<Item = Move>>(&mut self, iter: T) {
+        for mv in iter {
+            self.push(mv);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn should_push_and_retain() {
+        let mut moves = MoveList::new();
+        assert!(moves.is_empty());
+
+        moves.extend((0..8).map(|x| Move::Quiet {
+            from: (x, 6),
+            to: (x, 5),
+        }));
+        moves.retain_from(2, |mv| mv.from().0 % 2 == 0);
+
+        assert_eq!(
+            moves.iter().map(|mv| mv.from().0).collect::<Vec<_>>(),
+            vec![0, 1, 2, 4, 6]
+        );
+
+        moves.clear();
+        assert_eq!(moves.len(), 0);
+    }
+}
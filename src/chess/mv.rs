@@ -1,4 +1,4 @@
-use crate::PieceType;
+use crate::{error::OutOfBoardError, PieceType, Square};
 
 /// A move that can be applied to a game
 ///
@@ -7,7 +7,7 @@ use crate::PieceType;
 ///
 /// A good way to render a move is to check `from()` and `to()` first, if you need to render the
 /// capture square you can use `capture()`.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Move {
     /// A move that is not a capture
     Quiet {
@@ -47,6 +47,7 @@ pub enum Move {
     },
 }
 
+#[allow(clippy::match_like_matches_macro)]
 impl Move {
     pub fn is_double_pawn_push(&self) -> bool {
         match self {
@@ -114,6 +115,35 @@ impl Move {
         }
     }
 
+    /// Returns the move from square as a `Square`. If the move is a castle, it returns the king
+    /// square
+    ///
+    /// # Returns
+    /// * `Result<Square, OutOfBoardError>` - The from square, or an error if it's outside of the
+    ///   board
+    pub fn from_square(&self) -> Result<Square, OutOfBoardError> {
+        Square::try_from(self.from())
+    }
+
+    /// Returns the move to square as a `Square`. If the move is a castle, it returns the king
+    /// square
+    ///
+    /// # Returns
+    /// * `Result<Square, OutOfBoardError>` - The to square, or an error if it's outside of the
+    ///   board
+    pub fn to_square(&self) -> Result<Square, OutOfBoardError> {
+        Square::try_from(self.to())
+    }
+
+    /// Returns the square of the captured piece as a `Square`, if the move is a capture
+    ///
+    /// # Returns
+    /// * `Option<Result<Square, OutOfBoardError>>` - The capture square, or an error if it's
+    ///   outside of the board. If the move isn't a capture, this will return None
+    pub fn capture_square(&self) -> Option<Result<Square, OutOfBoardError>> {
+        self.capture().map(Square::try_from)
+    }
+
     pub fn capture(&self) -> Option<(usize, usize)> {
         match self {
             Move::Capture { capture, .. } | Move::CapturePromotion { capture, .. } => {
@@ -147,4 +177,42 @@ impl Move {
             _ => None,
         }
     }
+
+    /// Returns the move in UCI long algebraic notation
+    ///
+    /// Castling is written as the king move and promotions get the lowercase piece as a suffix.
+    ///
+    /// # Returns
+    /// * `Option<String>` - The move in UCI notation, e.g. `"e2e4"`, `"e1g1"` or `"a7a8q"`. If the
+    ///   move has squares outside of the board, this will return None
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::Move;
+    ///
+    /// let mv = Move::DoublePawnPush { from: (4, 6), to: (4, 4) };
+    /// assert_eq!(mv.uci(), Some("e2e4".to_string()));
+    ///
+    /// let mv = Move::Quiet { from: (0, 9), to: (0, 0) };
+    /// assert_eq!(mv.uci(), None);
+    /// ```
+    pub fn uci(&self) -> Option<String> {
+        let from = self.from_square().ok()?;
+        let to = self.to_square().ok()?;
+
+        let mut uci = format!("{}{}", from, to);
+
+        if let Some(promotion) = self.promotion() {
+            uci.push(match promotion {
+                PieceType::Knight => 'n',
+                PieceType::Bishop => 'b',
+                PieceType::Rook => 'r',
+                PieceType::Queen => 'q',
+                PieceType::Pawn => 'p',
+                PieceType::King => 'k',
+            });
+        }
+
+        Some(uci)
+    }
 }
@@ -0,0 +1,48 @@
+//! Internal helpers for converting between board coordinates and algebraic square names
+//!
+//! Remember that the board is stored upside down, y = 0 is rank 8 and y = 7 is rank 1.
+
+/// Returns the file letter of an x coordinate, 0 is 'a' and 7 is 'h'
+pub(crate) fn file_char(x: usize) -> char {
+    (b'a' + x as u8) as char
+}
+
+/// Returns the rank digit of a y coordinate, 0 is '8' and 7 is '1'
+pub(crate) fn rank_char(y: usize) -> char {
+    (b'1' + (7 - y) as u8) as char
+}
+
+/// Parses a file letter into an x coordinate
+pub(crate) fn parse_file(c: char) -> Option<usize> {
+    match c {
+        'a'..='h' => Some(c as usize - 'a' as usize),
+        _ => None,
+    }
+}
+
+/// Parses a rank digit into a y coordinate
+pub(crate) fn parse_rank(c: char) -> Option<usize> {
+    match c {
+        '1'..='8' => Some(7 - (c as usize - '1' as usize)),
+        _ => None,
+    }
+}
+
+/// Returns the algebraic name of a square, e.g. `(4, 6)` becomes `"e2"`
+pub(crate) fn square_name((x, y): (usize, usize)) -> String {
+    format!("{}{}", file_char(x), rank_char(y))
+}
+
+/// Parses an algebraic square name, e.g. `"e2"` becomes `(4, 6)`
+pub(crate) fn parse_square(s: &str) -> Option<(usize, usize)> {
+    let mut chars = s.chars();
+
+    let x = parse_file(chars.next()?)?;
+    let y = parse_rank(chars.next()?)?;
+
+    if chars.next().is_some() {
+        return None;
+    }
+
+    Some((x, y))
+}
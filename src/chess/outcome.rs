@@ -0,0 +1,152 @@
+use crate::{pgn::PgnResult, Color, Game};
+
+/// How a game ended
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Outcome {
+    /// The color that won, or None if the game is a draw
+    pub winner: Option<Color>,
+    /// Why the game ended
+    pub reason: Termination,
+}
+
+/// The reason that a game ended
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Termination {
+    /// The side to move is in check and has no legal moves
+    Checkmate,
+    /// The side to move isn't in check but has no legal moves
+    Stalemate,
+    /// Neither side has enough material to checkmate
+    InsufficientMaterial,
+    /// The same position occurred three times and a player claimed a draw
+    ThreefoldRepetition,
+    /// The same position occurred five times
+    FivefoldRepetition,
+    /// Fifty moves without a capture or pawn move and a player claimed a draw
+    FiftyMoves,
+    /// Seventy-five moves without a capture or pawn move
+    SeventyFiveMoves,
+    /// A player resigned
+    Resignation,
+    /// The players agreed to a draw
+    Agreement,
+    /// A player ran out of time
+    Timeout,
+}
+
+impl Outcome {
+    /// Returns a drawn outcome
+    pub fn draw(reason: Termination) -> Outcome {
+        Outcome {
+            winner: None,
+            reason,
+        }
+    }
+
+    /// Returns an outcome where a color won
+    pub fn win(winner: Color, reason: Termination) -> Outcome {
+        Outcome {
+            winner: Some(winner),
+            reason,
+        }
+    }
+}
+
+impl From<Outcome> for PgnResult {
+    fn from(outcome: Outcome) -> Self {
+        match outcome.winner {
+            Some(Color::White) => PgnResult::WhiteWins,
+            Some(Color::Black) => PgnResult::BlackWins,
+            None => PgnResult::Draw,
+        }
+    }
+}
+
+impl Game {
+    /// Returns how the game ended, if it has ended
+    ///
+    /// Only endings that happen automatically are checked, that is checkmate, stalemate,
+    /// insufficient material and the seventy-five-move rule. Draws that have to be claimed,
+    /// repetitions and everything that isn't decided on the board are handled by `GameHistory`.
+    ///
+    /// # Returns
+    /// * `Option<Outcome>` - The outcome of the game, or None if the game is still going
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{Color, Game, Termination};
+    ///
+    /// let game = Game::from_fen("R3k3/8/4K3/8/8/8/8/8 b - - 0 1").unwrap();
+    /// let outcome = game.outcome().unwrap();
+    ///
+    /// assert_eq!(outcome.winner, Some(Color::White));
+    /// assert_eq!(outcome.reason, Termination::Checkmate);
+    /// ```
+    pub fn outcome(&self) -> Option<Outcome> {
+        if self.gen_all_moves().is_none() {
+            return Some(if self.is_check() {
+                Outcome::win(self.get_turn().opposite(), Termination::Checkmate)
+            } else {
+                Outcome::draw(Termination::Stalemate)
+            });
+        }
+
+        if self.is_insufficient_material() {
+            return Some(Outcome::draw(Termination::InsufficientMaterial));
+        }
+
+        if self.is_seventy_five_move_draw() {
+            return Some(Outcome::draw(Termination::SeventyFiveMoves));
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn should_find_outcomes() {
+        let outcomes_to_test = vec![
+            (
+                "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+                None,
+            ),
+            (
+                "rnb1kbnr/pppp1ppp/8/4p3/6Pq/5P2/PPPPP2P/RNBQKBNR w KQkq - 1 3",
+                Some(Outcome::win(Color::Black, Termination::Checkmate)),
+            ),
+            (
+                "7k/5Q2/6K1/8/8/8/8/8 b - - 0 1",
+                Some(Outcome::draw(Termination::Stalemate)),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4KB2 w - - 0 1",
+                Some(Outcome::draw(Termination::InsufficientMaterial)),
+            ),
+            (
+                "4k3/8/8/8/8/8/8/4K2R w - - 150 100",
+                Some(Outcome::draw(Termination::SeventyFiveMoves)),
+            ),
+            ("4k3/8/8/8/8/8/8/4K2R w - - 149 100", None),
+        ];
+
+        for (fen, outcome) in outcomes_to_test {
+            assert_eq!(Game::from_fen(fen).unwrap().outcome(), outcome, "{}", fen);
+        }
+    }
+
+    #[test]
+    pub fn outcome_should_convert_to_pgn_result() {
+        assert_eq!(
+            PgnResult::from(Outcome::win(Color::White, Termination::Timeout)),
+            PgnResult::WhiteWins
+        );
+        assert_eq!(
+            PgnResult::from(Outcome::draw(Termination::Agreement)),
+            PgnResult::Draw
+        );
+    }
+}
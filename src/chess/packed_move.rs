@@ -0,0 +1,211 @@
+use crate::{error::PackMoveError, Game, Move, PieceType, Square};
+
+const QUIET: u16 = 0;
+const DOUBLE_PAWN_PUSH: u16 = 1;
+const KING_SIDE_CASTLE: u16 = 2;
+const QUEEN_SIDE_CASTLE: u16 = 3;
+const CAPTURE: u16 = 4;
+const EN_PASSANT: u16 = 5;
+/// Promotions set this bit, the two lowest bits are the promotion piece
+const PROMOTION: u16 = 8;
+/// Captures that promote set this bit together with `PROMOTION`
+const PROMOTION_CAPTURE: u16 = 4;
+
+const PROMOTION_PIECES: [PieceType; 4] = [
+    PieceType::Knight,
+    PieceType::Bishop,
+    PieceType::Rook,
+    PieceType::Queen,
+];
+
+/// A move packed into 16 bits
+///
+/// `Move` is large since every square is a pair of `usize`, which adds up in transposition tables
+/// and opening books. A `PackedMove` stores the from square in the lowest 6 bits, the to square
+/// in the next 6 bits and the kind of move in the highest 4 bits. A square `(x, y)` is stored as
+/// `y * 8 + x`, like in a `Bitboard`.
+///
+/// Packing a move never loses information, and a packed move is unpacked with the game it was
+/// played in, which makes sure that the move is legal.
+///
+/// # Examples
+/// ```
+/// use fritiofr_chess::{Game, PackedMove};
+///
+/// let game = Game::start_pos();
+/// let mv = game.parse_san("Nf3").unwrap();
+///
+/// let packed = PackedMove::try_from(mv).unwrap();
+/// assert_eq!(std::mem::size_of_val(&packed), 2);
+/// assert_eq!(packed.to_move(&game), Some(mv));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct PackedMove(pub u16);
+
+impl PackedMove {
+    /// Returns the from square of the move
+    pub fn from_square(&self) -> Square {
+        Square::from_index((self.0 & 0x3f) as usize).expect("6 bits are always on the board")
+    }
+
+    /// Returns the to square of the move
+    pub fn to_square(&self) -> Square {
+        Square::from_index(((self.0 >> 6) & 0x3f) as usize).expect("6 bits are always on the board")
+    }
+
+    /// Returns the kind of move, stored in the highest 4 bits
+    fn flags(&self) -> u16 {
+        self.0 >> 12
+    }
+
+    /// Unpacks the move
+    ///
+    /// # Arguments
+    /// * `game` - The game the move is played in
+    ///
+    /// # Returns
+    /// * `Option<Move>` - The move, or None if it isn't a legal move in the game
+    pub fn to_move(&self, game: &Game) -> Option<Move> {
+        let from: (usize, usize) = self.from_square().into();
+        let to: (usize, usize) = self.to_square().into();
+        let flags = self.flags();
+
+        let mv = match flags {
+            QUIET => Move::Quiet { from, to },
+            DOUBLE_PAWN_PUSH => Move::DoublePawnPush { from, to },
+            KING_SIDE_CASTLE => Move::Castle {
+                from,
+                to,
+                rook_from: (7, from.1),
+                rook_to: (5, from.1),
+            },
+            QUEEN_SIDE_CASTLE => Move::Castle {
+                from,
+                to,
+                rook_from: (0, from.1),
+                rook_to: (3, from.1),
+            },
+            CAPTURE => Move::Capture {
+                from,
+                to,
+                capture: to,
+            },
+            EN_PASSANT => Move::Capture {
+                from,
+                to,
+                capture: (to.0, from.1),
+            },
+            _ if flags & PROMOTION != 0 => {
+                let promotion = PROMOTION_PIECES[(flags & 3) as usize];
+
+                if flags & PROMOTION_CAPTURE != 0 {
+                    Move::CapturePromotion {
+                        from,
+                        to,
+                        capture: to,
+                        promotion,
+                    }
+                } else {
+                    Move::QuietPromotion {
+                        from,
+                        to,
+                        promotion,
+                    }
+                }
+            }
+            _ => return None,
+        };
+
+        game.gen_moves(from.0, from.1)?.contains(&mv).then_some(mv)
+    }
+}
+
+impl TryFrom<Move> for PackedMove {
+    type Error = PackMoveError;
+
+    fn try_from(mv: Move) -> Result<Self, Self::Error> {
+        let promotion = |piece_type| {
+            let index = PROMOTION_PIECES
+                .iter()
+                .position(|&p| p == piece_type)
+                .ok_or(PackMoveError::InvalidPromotion)?;
+
+            Ok::<_, PackMoveError>(PROMOTION | index as u16)
+        };
+
+        let flags = match mv {
+            Move::Quiet { .. } => QUIET,
+            Move::DoublePawnPush { .. } => DOUBLE_PAWN_PUSH,
+            Move::Castle { .. } if mv.is_king_side_castle() => KING_SIDE_CASTLE,
+            Move::Castle { .. } => QUEEN_SIDE_CASTLE,
+            Move::Capture { to, capture, .. } if to == capture => CAPTURE,
+            Move::Capture { .. } => EN_PASSANT,
+            Move::QuietPromotion { promotion: p, .. } => promotion(p)?,
+            Move::CapturePromotion { promotion: p, .. } => promotion(p)? | PROMOTION_CAPTURE,
+        };
+
+        let from = mv.from_square()?.index() as u16;
+        let to = mv.to_square()?.index() as u16;
+
+        Ok(PackedMove(from | to << 6 | flags << 12))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn should_round_trip_all_moves() {
+        let fens_to_test = vec![
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b kq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+        ];
+
+        for fen in fens_to_test {
+            let game = Game::from_fen(fen).unwrap();
+
+            for mv in game.gen_all_moves().unwrap() {
+                assert_eq!(
+                    PackedMove::try_from(mv).unwrap().to_move(&game),
+                    Some(mv),
+                    "{}",
+                    fen
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_reject_moves_from_other_positions() {
+        let game = Game::start_pos();
+        let mv = game.parse_san("e4").unwrap();
+
+        let mut after = game;
+        after.apply_move(mv).unwrap();
+
+        assert_eq!(PackedMove::try_from(mv).unwrap().to_move(&after), None);
+        assert_eq!(PackedMove(0xffff).to_move(&game), None);
+    }
+
+    #[test]
+    pub fn should_fail_to_pack_impossible_moves() {
+        assert!(matches!(
+            PackedMove::try_from(Move::QuietPromotion {
+                from: (0, 1),
+                to: (0, 0),
+                promotion: PieceType::King,
+            }),
+            Err(PackMoveError::InvalidPromotion)
+        ));
+        assert!(matches!(
+            PackedMove::try_from(Move::Quiet {
+                from: (0, 1),
+                to: (0, 8),
+            }),
+            Err(PackMoveError::OutOfBoard(_))
+        ));
+    }
+}
@@ -0,0 +1,172 @@
+//! Reading and writing games in Portable Game Notation (PGN)
+//!
+//! A PGN file holds one or more games, every game is made up of tag pairs like
+//! `[White "Fritiof"]` followed by the moves in SAN and a result token.
+
+use crate::{error::GameApplyMoveError, Game, Move};
+
+mod read;
+pub use read::*;
+
+mod write;
+
+/// A game that has been read from PGN or that is being recorded to be written as PGN
+///
+/// Moves can only be added with `push_move`, so every move is legal in the position it's played
+/// in.
+#[derive(Debug, Clone)]
+pub struct PgnGame {
+    /// All tag pairs of the game, in the order they were read
+    tags: Vec<(String, String)>,
+    /// The position the game started from
+    start: Game,
+    /// All moves of the main line
+    moves: Vec<Move>,
+    /// The position after all moves have been played
+    game: Game,
+    /// The result of the game
+    result: PgnResult,
+}
+
+impl PgnGame {
+    /// Creates a new game without any moves or tags
+    ///
+    /// # Arguments
+    /// * `start` - The position the game starts from
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{pgn::PgnGame, Game};
+    ///
+    /// let mut record = PgnGame::new(Game::start_pos());
+    /// record.set_tag("White", "Fritiof");
+    ///
+    /// let game = Game::start_pos();
+    /// record.push_move(game.parse_san("e4").unwrap()).unwrap();
+    /// ```
+    pub fn new(start: Game) -> PgnGame {
+        PgnGame {
+            tags: vec![],
+            start,
+            moves: vec![],
+            game: start,
+            result: PgnResult::Unknown,
+        }
+    }
+
+    /// Applies a move to the game and records it
+    ///
+    /// # Arguments
+    /// * `mv` - The move to apply, it has to be a legal move for the current position
+    ///
+    /// # Returns
+    /// * `Result<(), GameApplyMoveError>` - A result that holds nothing if the move was applied or
+    ///   an error if the move isn't legal
+    pub fn push_move(&mut self, mv: Move) -> Result<(), GameApplyMoveError> {
+        self.game.apply_move(mv)?;
+        self.moves.push(mv);
+
+        Ok(())
+    }
+
+    /// Returns the position the game started from
+    ///
+    /// # Return
+    /// * `Game` - The game before any moves were played
+    pub fn get_start(&self) -> Game {
+        self.start
+    }
+
+    /// Returns the current position
+    ///
+    /// # Return
+    /// * `Game` - The game after all moves have been played
+    pub fn get_game(&self) -> Game {
+        self.game
+    }
+
+    /// Returns all moves of the main line, in order
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    /// Returns all tag pairs of the game, in the order they were read or set
+    pub fn tags(&self) -> &[(String, String)] {
+        &self.tags
+    }
+
+    /// Returns the result of the game
+    pub fn get_result(&self) -> PgnResult {
+        self.result
+    }
+
+    /// Sets the result of the game
+    ///
+    /// # Arguments
+    /// * `result` - The result to write at the end of the movetext
+    pub fn set_result(&mut self, result: PgnResult) {
+        self.result = result;
+    }
+
+    /// Sets the value of a tag, replacing the old value if the tag already exists
+    ///
+    /// # Arguments
+    /// * `name` - The name of the tag, e.g. `"White"`
+    /// * `value` - The value of the tag
+    pub fn set_tag(&mut self, name: &str, value: &str) {
+        match self.tags.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) => *v = value.to_string(),
+            None => self.tags.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// Returns the value of a tag
+    ///
+    /// # Arguments
+    /// * `name` - The name of the tag, e.g. `"White"`
+    ///
+    /// # Returns
+    /// * `Option<&str>` - The value of the tag, if the tag doesn't exist this will return None
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// The result of a game, as written at the end of the movetext
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PgnResult {
+    /// `1-0`
+    WhiteWins,
+    /// `0-1`
+    BlackWins,
+    /// `1/2-1/2`
+    Draw,
+    /// `*`, the game is still going or the result is unknown
+    Unknown,
+}
+
+impl PgnResult {
+    /// Returns the result token
+    pub fn token(&self) -> &'static str {
+        match self {
+            PgnResult::WhiteWins => "1-0",
+            PgnResult::BlackWins => "0-1",
+            PgnResult::Draw => "1/2-1/2",
+            PgnResult::Unknown => "*",
+        }
+    }
+
+    /// Parses a result token
+    pub fn from_token(token: &str) -> Option<PgnResult> {
+        match token {
+            "1-0" => Some(PgnResult::WhiteWins),
+            "0-1" => Some(PgnResult::BlackWins),
+            "1/2-1/2" => Some(PgnResult::Draw),
+            "*" => Some(PgnResult::Unknown),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,414 @@
+use std::{iter::Peekable, str::CharIndices};
+
+use crate::{
+    error::{ParsePgnError, ParseSanError},
+    Game, Move,
+};
+
+use super::{PgnGame, PgnResult};
+
+/// Reads all games in a PGN string
+///
+/// Comments, NAGs and variations are skipped, only the main line of every game is replayed.
+///
+/// # Arguments
+/// * `pgn` - The PGN string, it can hold any amount of games
+///
+/// # Returns
+/// * `Result<Vec<PgnGame>, ParsePgnError>` - All games in the string, or an error if any of the
+///   games couldn't be read
+///
+/// # Examples
+/// ```
+/// use fritiofr_chess::pgn::{read_pgn, PgnResult};
+///
+/// let games = read_pgn("[White \"Fritiof\"]\n\n1. e4 e5 2. Qh5 Nc6 3. Bc4 Nf6 4. Qxf7# 1-0").unwrap();
+/// assert_eq!(games[0].tag("White"), Some("Fritiof"));
+/// assert_eq!(games[0].moves().len(), 7);
+/// assert_eq!(games[0].get_result(), PgnResult::WhiteWins);
+/// assert!(games[0].get_game().is_checkmate());
+/// ```
+pub fn read_pgn(pgn: &str) -> Result<Vec<PgnGame>, ParsePgnError> {
+    let mut tokens = Tokens {
+        pgn,
+        chars: pgn.char_indices().peekable(),
+    };
+
+    let mut games = vec![];
+    let mut builder: Option<Builder> = None;
+
+    while let Some(token) = tokens.next_token()? {
+        match token {
+            Token::Tag(name, value) => {
+                // A tag after the movetext means that a new game has started, even if the last
+                // game was missing its result
+                if builder.as_ref().is_some_and(|b| b.in_movetext) {
+                    games.push(
+                        builder
+                            .take()
+                            .expect("Checked above")
+                            .finish(PgnResult::Unknown),
+                    );
+                }
+
+                builder
+                    .get_or_insert_with(Builder::default)
+                    .tags
+                    .push((name, value));
+            }
+            Token::San(san) => builder
+                .get_or_insert_with(Builder::default)
+                .push_san(&san)?,
+            Token::Result(result) => {
+                games.push(builder.take().unwrap_or_default().finish(result));
+            }
+        }
+    }
+
+    if let Some(builder) = builder {
+        games.push(builder.finish(PgnResult::Unknown));
+    }
+
+    games.into_iter().collect()
+}
+
+impl PgnGame {
+    /// Reads the first game in a PGN string
+    ///
+    /// # Arguments
+    /// * `pgn` - The PGN string
+    ///
+    /// # Returns
+    /// * `Result<PgnGame, ParsePgnError>` - The first game in the string, or an error if there are
+    ///   no games or the game couldn't be read
+    pub fn from_pgn(pgn: &str) -> Result<PgnGame, ParsePgnError> {
+        read_pgn(pgn)?
+            .into_iter()
+            .next()
+            .ok_or(ParsePgnError::NoGame)
+    }
+}
+
+/// Internal struct that collects a game while it's being read
+#[derive(Default)]
+struct Builder {
+    tags: Vec<(String, String)>,
+    /// The start and current position, these are created once the first move is read since the
+    /// FEN tag has to be known
+    games: Option<(Game, Game)>,
+    moves: Vec<Move>,
+    in_movetext: bool,
+}
+
+impl Builder {
+    fn start(&self) -> Result<Game, ParsePgnError> {
+        let fen = self
+            .tags
+            .iter()
+            .find(|(name, _)| name == "FEN")
+            .map(|(_, fen)| fen);
+
+        match fen {
+            // Some PGN writers leave out the halfmove clock and fullmove number
+            Some(fen) => Ok(Game::from_fen_lenient(fen)?),
+            None => Ok(Game::start_pos()),
+        }
+    }
+
+    fn games(&mut self) -> Result<&mut (Game, Game), ParsePgnError> {
+        if self.games.is_none() {
+            let start = self.start()?;
+            self.games = Some((start, start));
+        }
+
+        Ok(self.games.as_mut().expect("Set above"))
+    }
+
+    fn push_san(&mut self, san: &str) -> Result<(), ParsePgnError> {
+        self.in_movetext = true;
+
+        let ply = self.moves.len() + 1;
+        let (_, game) = self.games()?;
+
+        let illegal_move = |source| ParsePgnError::IllegalMove {
+            san: san.to_string(),
+            ply,
+            source,
+        };
+
+        let mv = game.parse_san(san).map_err(illegal_move)?;
+
+        game.apply_move(mv)
+            .map_err(|_| illegal_move(ParseSanError::IllegalMove))?;
+
+        self.moves.push(mv);
+
+        Ok(())
+    }
+
+    fn finish(mut self, result: PgnResult) -> Result<PgnGame, ParsePgnError> {
+        let (start, game) = *self.games()?;
+
+        Ok(PgnGame {
+            tags: self.tags,
+            start,
+            moves: self.moves,
+            game,
+            result,
+        })
+    }
+}
+
+enum Token {
+    Tag(String, String),
+    San(String),
+    Result(PgnResult),
+}
+
+/// Internal tokenizer over a PGN string, that skips everything that isn't needed to replay the
+/// main line
+struct Tokens<'a> {
+    pgn: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+}
+
+impl<'a> Tokens<'a> {
+    fn next_token(&mut self) -> Result<Option<Token>, ParsePgnError> {
+        loop {
+            let Some(&(start, c)) = self.chars.peek() else {
+                return Ok(None);
+            };
+
+            match c {
+                '[' => {
+                    self.chars.next();
+                    return self.tag().map(Some);
+                }
+                '{' | ';' | '(' => self.skip_comment_or_variation()?,
+                ')' => return Err(ParsePgnError::UnbalancedVariation),
+                '%' if start == 0 || self.pgn[..start].ends_with('\n') => self.skip_line(),
+                '$' => {
+                    self.chars.next();
+                    self.word();
+                }
+                c if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                _ => {
+                    let word = self.word();
+
+                    if word.is_empty() {
+                        return Err(ParsePgnError::UnexpectedCharacter(c));
+                    }
+
+                    if let Some(result) = PgnResult::from_token(word) {
+                        return Ok(Some(Token::Result(result)));
+                    }
+
+                    // Strip move numbers like "12." and "12..." which can be glued to the move
+                    let digits =
+                        word.len() - word.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                    let san = if digits > 0 && word[digits..].starts_with('.') {
+                        word[digits..].trim_start_matches('.')
+                    } else {
+                        word
+                    };
+
+                    if !san.is_empty() {
+                        return Ok(Some(Token::San(san.to_string())));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reads a word up until whitespace or a special character
+    fn word(&mut self) -> &'a str {
+        let start = self.chars.peek().map_or(self.pgn.len(), |(i, _)| *i);
+
+        while let Some(&(_, c)) = self.chars.peek() {
+            if c.is_whitespace() || matches!(c, '[' | ']' | '{' | '}' | '(' | ')' | ';' | '$') {
+                break;
+            }
+            self.chars.next();
+        }
+
+        let end = self.chars.peek().map_or(self.pgn.len(), |(i, _)| *i);
+
+        &self.pgn[start..end]
+    }
+
+    fn skip_line(&mut self) {
+        for (_, c) in self.chars.by_ref() {
+            if c == '\n' {
+                break;
+            }
+        }
+    }
+
+    /// Skips a brace comment, the opening brace has already been consumed
+    fn skip_brace_comment(&mut self) -> Result<(), ParsePgnError> {
+        if self.chars.by_ref().any(|(_, c)| c == '}') {
+            Ok(())
+        } else {
+            Err(ParsePgnError::UnterminatedComment)
+        }
+    }
+
+    /// Skips a comment or a variation, variations can be nested and hold comments of their own
+    fn skip_comment_or_variation(&mut self) -> Result<(), ParsePgnError> {
+        let mut depth = 0;
+
+        while let Some((_, c)) = self.chars.next() {
+            match c {
+                '{' => self.skip_brace_comment()?,
+                ';' => self.skip_line(),
+                '(' => depth += 1,
+                ')' => depth -= 1,
+                _ => (),
+            }
+
+            if depth == 0 {
+                return Ok(());
+            }
+        }
+
+        Err(ParsePgnError::UnbalancedVariation)
+    }
+
+    /// Reads a tag pair, the opening bracket has already been consumed
+    fn tag(&mut self) -> Result<Token, ParsePgnError> {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+
+        let name = self.word().to_string();
+
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+
+        if name.is_empty() || self.chars.next().map(|(_, c)| c) != Some('"') {
+            return Err(ParsePgnError::InvalidTag);
+        }
+
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => break,
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, c)) => value.push(c),
+                    None => return Err(ParsePgnError::InvalidTag),
+                },
+                Some((_, c)) => value.push(c),
+                None => return Err(ParsePgnError::InvalidTag),
+            }
+        }
+
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+
+        if self.chars.next().map(|(_, c)| c) != Some(']') {
+            return Err(ParsePgnError::InvalidTag);
+        }
+
+        Ok(Token::Tag(name, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPERA_GAME: &str = r#"[Event "Paris"]
+[Site "Paris FRA"]
+[Date "1858.??.??"]
+[Round "?"]
+[White "Paul Morphy"]
+[Black "Duke Karl / Count Isouard"]
+[Result "1-0"]
+
+1.e4 e5 2.Nf3 d6 3.d4 Bg4 {This is a weak move already.--Fischer} 4.dxe5 Bxf3
+5.Qxf3 dxe5 6.Bc4 Nf6 7.Qb3 Qe7 8.Nc3 c6 9.Bg5 {Black is in what's like a
+zugzwang position here.} b5 10.Nxb5 cxb5 11.Bxb5+ Nbd7 12.O-O-O Rd8
+13.Rxd7 Rxd7 14.Rd1 Qe6 15.Bxd7+ Nxd7 16.Qb8+ Nxb8 17.Rd8# 1-0
+"#;
+
+    #[test]
+    pub fn should_read_game() {
+        let game = PgnGame::from_pgn(OPERA_GAME).unwrap();
+
+        assert_eq!(game.tags().len(), 7);
+        assert_eq!(game.tag("White"), Some("Paul Morphy"));
+        assert_eq!(game.moves().len(), 33);
+        assert_eq!(game.get_result(), PgnResult::WhiteWins);
+        assert_eq!(game.get_start(), Game::start_pos());
+        assert!(game.get_game().is_checkmate());
+    }
+
+    #[test]
+    pub fn should_read_multiple_games() {
+        let pgn = format!(
+            "{}\n\n{}\n[Event \"No result\"]\n\n1. d4 d5",
+            OPERA_GAME, "[Event \"Short\"]\n\n1. f3 e5 2. g4 Qh4# 0-1"
+        );
+        let games = read_pgn(&pgn).unwrap();
+
+        assert_eq!(games.len(), 3);
+        assert_eq!(games[1].get_result(), PgnResult::BlackWins);
+        assert_eq!(games[1].moves().len(), 4);
+        assert_eq!(games[2].tag("Event"), Some("No result"));
+        assert_eq!(games[2].get_result(), PgnResult::Unknown);
+        assert_eq!(games[2].moves().len(), 2);
+    }
+
+    #[test]
+    pub fn should_skip_comments_nags_and_variations() {
+        let pgn = "1. e4 $1 {A comment (with parenthesis)} e5 ; rest of line is a comment 1. d4\n\
+                   2. Nf3 (2. f4 exf4 (2... d5 {nested}) 3. Nf3) 2... Nc6 $14 3. Bb5 a6 *";
+        let game = PgnGame::from_pgn(pgn).unwrap();
+
+        assert_eq!(game.moves().len(), 6);
+        assert_eq!(game.get_result(), PgnResult::Unknown);
+        assert_eq!(
+            game.get_game().fen(),
+            "r1bqkbnr/1ppp1ppp/p1n5/1B2p3/4P3/5N2/PPPP1PPP/RNBQK2R w KQkq - 0 4"
+        );
+    }
+
+    #[test]
+    pub fn should_start_from_fen() {
+        let pgn = "[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 1\"]\n\n1... Kd7 2. e4 *";
+        let game = PgnGame::from_pgn(pgn).unwrap();
+
+        assert_eq!(game.get_start().fen(), "4k3/8/8/8/8/8/4P3/4K3 b - - 0 1");
+        assert_eq!(game.moves().len(), 2);
+        assert_eq!(game.get_game().fen(), "8/3k4/8/8/4P3/8/8/4K3 b - e3 0 2");
+    }
+
+    #[test]
+    pub fn should_fail_on_bad_pgn() {
+        assert!(matches!(
+            PgnGame::from_pgn("1. e4 e4 *"),
+            Err(ParsePgnError::IllegalMove { ply: 2, .. })
+        ));
+        assert!(matches!(
+            PgnGame::from_pgn("1. e4 {unterminated"),
+            Err(ParsePgnError::UnterminatedComment)
+        ));
+        assert!(matches!(
+            PgnGame::from_pgn("1. e4 (1. d4 *"),
+            Err(ParsePgnError::UnbalancedVariation)
+        ));
+        assert!(matches!(
+            PgnGame::from_pgn("[Event Paris]"),
+            Err(ParsePgnError::InvalidTag)
+        ));
+        assert!(matches!(
+            PgnGame::from_pgn("1. e4 } e5"),
+            Err(ParsePgnError::UnexpectedCharacter('}'))
+        ));
+        assert!(matches!(PgnGame::from_pgn(""), Err(ParsePgnError::NoGame)));
+
+        // Castling rights in the FEN tag without the king on its starting square
+        assert!(matches!(
+            PgnGame::from_pgn("[FEN \"4k3/8/8/8/8/8/8/3K3R w K - 0 1\"]\n\n1. O-O *"),
+            Err(ParsePgnError::IllegalMove { ply: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,202 @@
+use crate::{Color, Game};
+
+use super::PgnGame;
+
+/// The tags that every PGN game has to start with, in this order
+const SEVEN_TAG_ROSTER: [(&str, &str); 7] = [
+    ("Event", "?"),
+    ("Site", "?"),
+    ("Date", "????.??.??"),
+    ("Round", "?"),
+    ("White", "?"),
+    ("Black", "?"),
+    ("Result", "*"),
+];
+
+/// The maximum length of a line in the movetext
+const MAX_LINE_LENGTH: usize = 80;
+
+impl PgnGame {
+    /// Returns the game as a PGN string
+    ///
+    /// The seven tag roster is always written first, missing tags get their unknown value. If the
+    /// game didn't start from the starting position, the `SetUp` and `FEN` tags are added.
+    ///
+    /// # Returns
+    /// * `String` - The game as a PGN string
+    ///
+    /// # Examples
+    /// ```
+    /// use fritiofr_chess::{pgn::{PgnGame, PgnResult}, Game};
+    ///
+    /// let mut record = PgnGame::new(Game::start_pos());
+    /// for san in ["f3", "e5", "g4", "Qh4"] {
+    ///     record.push_move(record.get_game().parse_san(san).unwrap()).unwrap();
+    /// }
+    /// record.set_result(PgnResult::BlackWins);
+    ///
+    /// assert!(record.to_pgn().ends_with("1. f3 e5 2. g4 Qh4# 0-1\n"));
+    /// ```
+    pub fn to_pgn(&self) -> String {
+        let mut pgn = String::new();
+
+        for (name, default) in SEVEN_TAG_ROSTER {
+            let value = match name {
+                "Result" => self.result.token(),
+                _ => self.tag(name).unwrap_or(default),
+            };
+
+            push_tag(&mut pgn, name, value);
+        }
+
+        let custom_start = self.start != Game::start_pos();
+        if custom_start {
+            push_tag(&mut pgn, "SetUp", "1");
+            push_tag(&mut pgn, "FEN", &self.start.fen());
+        }
+
+        for (name, value) in &self.tags {
+            let skip = SEVEN_TAG_ROSTER.iter().any(|(n, _)| n == name)
+                || (custom_start && (name == "SetUp" || name == "FEN"));
+
+            if !skip {
+                push_tag(&mut pgn, name, value);
+            }
+        }
+
+        pgn.push('\n');
+
+        let mut line = String::new();
+        let mut game = self.start;
+
+        for (i, mv) in self.moves.iter().enumerate() {
+            let turn = game.get_turn();
+            let move_number = game.get_fullmove_number();
+
+            if turn == Color::White {
+                push_word(&mut pgn, &mut line, &format!("{}.", move_number));
+            } else if i == 0 {
+                push_word(&mut pgn, &mut line, &format!("{}...", move_number));
+            }
+
+            let san = game.san(*mv).expect("Moves in a PgnGame are always legal");
+            push_word(&mut pgn, &mut line, &san);
+
+            game.apply_move(*mv)
+                .expect("Moves in a PgnGame are always legal");
+        }
+
+        push_word(&mut pgn, &mut line, self.result.token());
+
+        pgn.push_str(&line);
+        pgn.push('\n');
+
+        pgn
+    }
+}
+
+impl std::fmt::Display for PgnGame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_pgn())
+    }
+}
+
+/// Writes a tag pair on its own line, quotes and backslashes in the value are escaped
+fn push_tag(pgn: &mut String, name: &str, value: &str) {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+
+    pgn.push_str(&format!("[{} \"{}\"]\n", name, value));
+}
+
+/// Adds a word to the current movetext line, if the line would become too long it's written to
+/// the PGN string and a new line is started
+fn push_word(pgn: &mut String, line: &mut String, word: &str) {
+    if !line.is_empty() && line.len() + 1 + word.len() > MAX_LINE_LENGTH {
+        pgn.push_str(line);
+        pgn.push('\n');
+        line.clear();
+    }
+
+    if !line.is_empty() {
+        line.push(' ');
+    }
+
+    line.push_str(word);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pgn::{read_pgn, PgnResult};
+
+    use super::*;
+
+    #[test]
+    pub fn should_write_tags() {
+        let mut record = PgnGame::new(Game::start_pos());
+        record.set_tag("White", "Fritiof \"The Great\"");
+        record.set_tag("Annotator", "Me");
+        record.set_tag("Event", "Club game");
+        record.set_result(PgnResult::Draw);
+
+        assert_eq!(
+            record.to_pgn(),
+            "[Event \"Club game\"]\n\
+             [Site \"?\"]\n\
+             [Date \"????.??.??\"]\n\
+             [Round \"?\"]\n\
+             [White \"Fritiof \\\"The Great\\\"\"]\n\
+             [Black \"?\"]\n\
+             [Result \"1/2-1/2\"]\n\
+             [Annotator \"Me\"]\n\
+             \n\
+             1/2-1/2\n"
+        );
+    }
+
+    #[test]
+    pub fn should_write_fen_and_black_first_move() {
+        let mut record = PgnGame::new(Game::from_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 3 40").unwrap());
+        record
+            .push_move(record.get_game().parse_san("Kd7").unwrap())
+            .unwrap();
+        record
+            .push_move(record.get_game().parse_san("e4").unwrap())
+            .unwrap();
+
+        let pgn = record.to_pgn();
+        assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 3 40\"]\n"));
+        assert!(pgn.ends_with("\n40... Kd7 41. e4 *\n"));
+    }
+
+    #[test]
+    pub fn should_reject_illegal_moves() {
+        let mut record = PgnGame::new(Game::start_pos());
+        let mv = Game::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1")
+            .unwrap()
+            .parse_san("e5")
+            .unwrap();
+
+        assert!(record.push_move(mv).is_err());
+        assert!(record.moves().is_empty());
+    }
+
+    #[test]
+    pub fn should_wrap_and_round_trip() {
+        let pgn =
+            "[Event \"Paris\"]\n\n1.e4 e5 2.Nf3 d6 3.d4 Bg4 4.dxe5 Bxf3 5.Qxf3 dxe5 6.Bc4 Nf6 \
+                   7.Qb3 Qe7 8.Nc3 c6 9.Bg5 b5 10.Nxb5 cxb5 11.Bxb5+ Nbd7 12.O-O-O Rd8 13.Rxd7 \
+                   Rxd7 14.Rd1 Qe6 15.Bxd7+ Nxd7 16.Qb8+ Nxb8 17.Rd8# 1-0";
+        let record = PgnGame::from_pgn(pgn).unwrap();
+        let written = record.to_pgn();
+
+        assert!(written.lines().all(|line| line.len() <= MAX_LINE_LENGTH));
+        assert!(written.contains("12. O-O-O Rd8"));
+        assert!(written.ends_with("17. Rd8# 1-0\n"));
+
+        let read = read_pgn(&written).unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].moves(), record.moves());
+        assert_eq!(read[0].tags().len(), 7);
+        assert_eq!(read[0].to_pgn(), written);
+    }
+}
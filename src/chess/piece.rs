@@ -3,14 +3,14 @@ use std::str::FromStr;
 use crate::error::ParsePieceError;
 
 /// A piece on the board
-#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
 pub struct Piece {
     pub piece_type: PieceType,
     pub color: Color,
 }
 
 /// A type of piece
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone)]
 pub enum PieceType {
     Pawn,
     Knight,
@@ -21,7 +21,7 @@ pub enum PieceType {
 }
 
 /// Either white or black
-#[derive(Copy, Debug, Clone, PartialEq, Eq)]
+#[derive(Copy, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Color {
     White,
     Black,
@@ -40,6 +40,7 @@ impl Color {
     }
 }
 
+#[allow(clippy::from_over_into)]
 impl Into<char> for Piece {
     fn into(self) -> char {
         let piece_char = match self.piece_type {
@@ -95,7 +96,7 @@ impl FromStr for Piece {
             .chars()
             .next()
             .ok_or(ParsePieceError::StringEmpty)
-            .and_then(|c| Piece::try_from(c));
+            .and_then(Piece::try_from);
 
         piece
     }
@@ -0,0 +1,238 @@
+use std::{fmt, str::FromStr};
+
+use crate::{
+    chess::notation::{file_char, parse_file, parse_rank, rank_char},
+    error::{OutOfBoardError, ParseSquareError},
+};
+
+/// A column of the board, from the a-file to the h-file
+///
+/// A file is always on the board, it's the same as an x coordinate between 0 and 7.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct File(u8);
+
+/// A row of the board, from the first rank to the eighth rank
+///
+/// A rank is always on the board. Remember that the board is stored upside down, the eighth rank
+/// is y = 0 and the first rank is y = 7.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Rank(u8);
+
+/// A square on the board
+///
+/// Squares are always on the board, they parse from and display as their algebraic name like
+/// `"e4"`, and convert to and from the `(x, y)` coordinates used by the rest of the library.
+///
+/// # Examples
+/// ```
+/// use fritiofr_chess::Square;
+///
+/// let square: Square = "e4".parse().unwrap();
+///
+/// assert_eq!(square.to_string(), "e4");
+/// assert_eq!(<(usize, usize)>::from(square), (4, 4));
+/// assert_eq!(Square::try_from((4, 4)).unwrap(), square);
+/// assert!(Square::try_from((8, 0)).is_err());
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Square(u8);
+
+impl File {
+    /// Returns the file of an x coordinate, or None if it's outside of the board
+    pub fn from_x(x: usize) -> Option<File> {
+        (x < 8).then_some(File(x as u8))
+    }
+
+    /// Returns the x coordinate of the file
+    pub fn x(&self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl Rank {
+    /// Returns the rank of a y coordinate, or None if it's outside of the board
+    pub fn from_y(y: usize) -> Option<Rank> {
+        (y < 8).then_some(Rank(y as u8))
+    }
+
+    /// Returns the y coordinate of the rank
+    pub fn y(&self) -> usize {
+        self.0 as usize
+    }
+
+    /// Returns the number of the rank, 1 for the first rank and 8 for the eighth rank
+    pub fn number(&self) -> usize {
+        8 - self.0 as usize
+    }
+}
+
+impl Square {
+    /// Returns the square on a file and a rank
+    pub fn new(file: File, rank: Rank) -> Square {
+        Square(rank.0 * 8 + file.0)
+    }
+
+    /// Returns the file of the square
+    pub fn file(&self) -> File {
+        File(self.0 % 8)
+    }
+
+    /// Returns the rank of the square
+    pub fn rank(&self) -> Rank {
+        Rank(self.0 / 8)
+    }
+
+    /// Returns the x coordinate of the square
+    pub fn x(&self) -> usize {
+        self.file().x()
+    }
+
+    /// Returns the y coordinate of the square
+    pub fn y(&self) -> usize {
+        self.rank().y()
+    }
+
+    /// Returns the index of the square, `y * 8 + x`, the same index as in a `Bitboard`
+    pub fn index(&self) -> usize {
+        self.0 as usize
+    }
+
+    /// Returns the square of an index, or None if the index is 64 or more
+    pub fn from_index(index: usize) -> Option<Square> {
+        (index < 64).then_some(Square(index as u8))
+    }
+}
+
+impl TryFrom<(usize, usize)> for Square {
+    type Error = OutOfBoardError;
+
+    fn try_from((x, y): (usize, usize)) -> Result<Self, Self::Error> {
+        match (File::from_x(x), Rank::from_y(y)) {
+            (Some(file), Some(rank)) => Ok(Square::new(file, rank)),
+            _ => Err(OutOfBoardError),
+        }
+    }
+}
+
+impl From<Square> for (usize, usize) {
+    fn from(square: Square) -> Self {
+        (square.x(), square.y())
+    }
+}
+
+impl TryFrom<char> for File {
+    type Error = ParseSquareError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        parse_file(c)
+            .map(|x| File(x as u8))
+            .ok_or(ParseSquareError::InvalidFile)
+    }
+}
+
+impl TryFrom<char> for Rank {
+    type Error = ParseSquareError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        parse_rank(c)
+            .map(|y| Rank(y as u8))
+            .ok_or(ParseSquareError::InvalidRank)
+    }
+}
+
+impl FromStr for Square {
+    type Err = ParseSquareError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+
+        let (Some(file), Some(rank), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(ParseSquareError::IncorrectLength);
+        };
+
+        Ok(Square::new(File::try_from(file)?, Rank::try_from(rank)?))
+    }
+}
+
+impl fmt::Display for File {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", file_char(self.x()))
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", rank_char(self.y()))
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.file(), self.rank())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Move;
+
+    #[test]
+    pub fn should_parse_and_display_squares() {
+        for index in 0..64 {
+            let square = Square::from_index(index).unwrap();
+            assert_eq!(square.to_string().parse::<Square>().unwrap(), square);
+        }
+
+        let a8: Square = "a8".parse().unwrap();
+        assert_eq!((a8.x(), a8.y()), (0, 0));
+        assert_eq!(a8.rank().number(), 8);
+
+        let h1: Square = "h1".parse().unwrap();
+        assert_eq!(h1.index(), 63);
+        assert_eq!(h1.file().to_string(), "h");
+        assert_eq!(h1.rank().to_string(), "1");
+
+        assert!(matches!(
+            "i1".parse::<Square>(),
+            Err(ParseSquareError::InvalidFile)
+        ));
+        assert!(matches!(
+            "a9".parse::<Square>(),
+            Err(ParseSquareError::InvalidRank)
+        ));
+        assert!(matches!(
+            "e44".parse::<Square>(),
+            Err(ParseSquareError::IncorrectLength)
+        ));
+        assert!(Square::from_index(64).is_none());
+    }
+
+    #[test]
+    pub fn moves_should_convert_their_squares() {
+        let mv = Move::Capture {
+            from: (4, 4),
+            to: (3, 3),
+            capture: (3, 3),
+        };
+        assert_eq!(mv.from_square().unwrap().to_string(), "e4");
+        assert_eq!(mv.to_square().unwrap().to_string(), "d5");
+        assert_eq!(mv.capture_square().unwrap().unwrap().to_string(), "d5");
+
+        // Moves are plain coordinates, so they can point outside of the board
+        let mv = Move::Capture {
+            from: (4, 4),
+            to: (8, 3),
+            capture: (3, 9),
+        };
+        assert!(mv.from_square().is_ok());
+        assert!(mv.to_square().is_err());
+        assert!(matches!(mv.capture_square(), Some(Err(OutOfBoardError))));
+        assert!(Move::Quiet {
+            from: (4, 6),
+            to: (4, 5)
+        }
+        .capture_square()
+        .is_none());
+    }
+}